@@ -8,7 +8,15 @@ use bevy::{
     prelude::{Resource, *},
 };
 use polako_constructivism::{traits::Construct, *};
-use polako_flow::{EnterSignal, NotifyChange, OnDemandSignal, UpdateSignal};
+use polako_flow::{
+    input::{
+        BlurSignalMarker, CharSignalMarker, FocusSignalMarker, KeyDownSignalMarker,
+        KeyUpSignalMarker,
+    },
+    EnterSignal, ExitSignal, NotifyChange, OnDemandSignal, UpdateSignal,
+};
+
+pub use polako_input::Focusable;
 
 #[cfg(test)]
 mod tests;
@@ -75,6 +83,8 @@ where
     Self::Type: Component,
 {
     fn notify_changed(&self, commands: &mut Commands, entity: Entity);
+    /// Notifies only the readers of the `prop` (`bg.r`).
+    fn notify_prop_changed(&self, commands: &mut Commands, entity: Entity, prop: &'static str);
 }
 
 impl<T: TypeReference> PolakoType for T
@@ -84,6 +94,9 @@ where
     fn notify_changed(&self, commands: &mut Commands, entity: Entity) {
         commands.add(NotifyChange::<Self::Type>::new(entity))
     }
+    fn notify_prop_changed(&self, commands: &mut Commands, entity: Entity, prop: &'static str) {
+        commands.add(NotifyChange::<Self::Type>::new(entity).prop(prop))
+    }
 }
 
 pub struct EntityMark<E: Element> {
@@ -110,6 +123,30 @@ impl<E: Element> EntityMark<E> {
 }
 
 impl<E: Element> Copy for EntityMark<E> {}
+
+/// The names of the marks the entities were declared with in `eml!`,
+/// `label` for `label: Label { }`. The marks are recorded only if the
+/// resource exists (the test harness inserts it), so the elements don't
+/// carry any extra component. Blueprint marks are not stored.
+#[derive(Resource, Default)]
+pub struct EmlMarks(Vec<(&'static str, Entity)>);
+
+impl EmlMarks {
+    pub fn record(world: &mut World, entity: Entity, mark: &'static str) {
+        if let Some(mut marks) = world.get_resource_mut::<EmlMarks>() {
+            marks.0.push((mark, entity));
+        }
+    }
+
+    /// The entities declared with the `mark`, in the declaration order.
+    pub fn get<'a>(&'a self, mark: &'a str) -> impl Iterator<Item = Entity> + 'a {
+        self.0
+            .iter()
+            .filter(move |(m, _)| *m == mark)
+            .map(|(_, e)| *e)
+    }
+}
+
 impl<E: Element> std::fmt::Debug for EntityMark<E> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("EntityMark")
@@ -213,6 +250,61 @@ impl EmptySignals {
     pub fn update(&self) -> &'static OnDemandSignal<UpdateSignal> {
         OnDemandSignal::instance()
     }
+
+    /// Emits before the element is despawned with `WorldFlow::exit` or removed
+    /// from its parent. The hands may `e.defer()` the despawn until
+    /// `e.complete_exit()` or `element.complete_exit()`.
+    pub fn exit(&self) -> &'static OnDemandSignal<ExitSignal> {
+        OnDemandSignal::instance()
+    }
+}
+
+impl IntoBundle for Focusable {
+    type Output = Self;
+    fn into_bundle(self) -> Self::Output {
+        self
+    }
+}
+
+/// Focuses the element by pointer and by Tab / Shift+Tab:
+/// `#[construct(Input -> Focusable -> Div)]`.
+impl Behavior for Focusable {
+    type Signals<T: Singleton + 'static> = FocusableSignals<T>;
+}
+
+pub struct FocusableSignals<T>(PhantomData<T>);
+impl<T: Singleton + 'static> std::ops::Deref for FocusableSignals<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        T::instance()
+    }
+}
+impl<T: Singleton + 'static> Singleton for FocusableSignals<T> {
+    fn instance() -> &'static Self {
+        &FocusableSignals(PhantomData)
+    }
+}
+impl<T: Singleton + 'static> FocusableSignals<T> {
+    pub fn focus(&self) -> &'static FocusSignalMarker {
+        FocusSignalMarker::instance()
+    }
+
+    pub fn blur(&self) -> &'static BlurSignalMarker {
+        BlurSignalMarker::instance()
+    }
+
+    /// The key signals are sent to the focused element and bubble up.
+    pub fn key_down(&self) -> &'static KeyDownSignalMarker {
+        KeyDownSignalMarker::instance()
+    }
+
+    pub fn key_up(&self) -> &'static KeyUpSignalMarker {
+        KeyUpSignalMarker::instance()
+    }
+
+    pub fn char(&self) -> &'static CharSignalMarker {
+        CharSignalMarker::instance()
+    }
 }
 
 impl EmptyDesign {
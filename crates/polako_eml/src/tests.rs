@@ -1,4 +1,6 @@
 use bevy::ecs::component::TableStorage;
+use polako_flow::FlowPlugin;
+use polako_input::PointerFilter;
 
 use super::*;
 
@@ -219,3 +221,558 @@ fn test_eml_mix_patch() {
         &world.query::<&TestComponent>().single(world).value
     );
 }
+
+#[derive(Behavior)]
+pub struct Inventory {
+    pub items: Vec<String>,
+}
+
+impl Default for Inventory {
+    fn default() -> Self {
+        Inventory {
+            items: vec!["a".into(), "b".into()],
+        }
+    }
+}
+
+#[derive(Element)]
+#[construct(List -> Inventory -> Div)]
+pub struct List;
+
+impl ElementBuilder for List {
+    fn build_element(_: Vec<Entity>) -> Blueprint<Self> {
+        blueprint! { List::Base }
+    }
+}
+
+fn texts(world: &mut World) -> Vec<String> {
+    let mut texts = world
+        .query::<&TextElement>()
+        .iter(world)
+        .map(|t| t.text.clone())
+        .collect::<Vec<_>>();
+    texts.sort();
+    texts
+}
+
+#[test]
+fn test_hand_indexed_reads() {
+    let mut app = App::new();
+    app.add_plugins(FlowPlugin::default());
+    let eml = eml! {
+        Div [
+            list: List,
+            first: Label { .text: "first" },
+            missing: Label { .text: "missing" },
+            Div {
+                .on.enter: () => {
+                    first.text = list.items[0];
+                    // the missing item skips the statement, not the whole hand
+                    missing.text = list.items[5];
+                    missing.font = list.items[1];
+                }
+            }
+        ]
+    };
+    eml.apply(&mut app.world);
+    app.update();
+    let world = &mut app.world;
+    assert_eq!(vec!["a".to_string(), "missing".to_string()], texts(world));
+    let fonts = world
+        .query::<&TextElement>()
+        .iter(world)
+        .filter(|t| t.text == "missing")
+        .map(|t| t.font.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(vec!["b".to_string()], fonts);
+}
+
+#[test]
+fn test_hand_indexed_writes() {
+    let mut app = App::new();
+    app.add_plugins(FlowPlugin::default());
+    let eml = eml! {
+        Div [
+            list: List,
+            Div {
+                .on.enter: () => {
+                    list.items[1] = "c";
+                    // out of range, skipped
+                    list.items[7] = "d";
+                    list.items[0] = "e";
+                }
+            }
+        ]
+    };
+    eml.apply(&mut app.world);
+    app.update();
+    let world = &mut app.world;
+    assert_eq!(
+        vec!["e".to_string(), "c".to_string()],
+        world.query::<&Inventory>().single(world).items
+    );
+}
+
+fn bold_text(world: &mut World) -> String {
+    world
+        .query::<&TextElement>()
+        .iter(world)
+        .find(|t| t.font == "bold")
+        .map(|t| t.text.clone())
+        .unwrap_or_default()
+}
+
+fn set_bold_text(world: &mut World, text: &str) {
+    let mut query = world.query::<&mut TextElement>();
+    for mut element in query.iter_mut(world).filter(|t| t.font == "bold") {
+        element.text = text.into();
+    }
+}
+
+#[test]
+fn test_eml_prop_change() {
+    let mut app = App::new();
+    app.add_plugins(FlowPlugin::default());
+    let eml = eml! {
+        Div [
+            Label {
+                .text: "a",
+                .on.change.text: (e) => {
+                    log.text = e.new;
+                }
+            },
+            log: Bold
+        ]
+    };
+    eml.apply(&mut app.world);
+    app.update();
+    let mut query = app.world.query::<&mut TextElement>();
+    for mut element in query.iter_mut(&mut app.world).filter(|t| t.font != "bold") {
+        element.text = "b".into();
+    }
+    app.update();
+    assert_eq!("b", bold_text(&mut app.world));
+}
+
+#[derive(Resource, Construct, Clone, Debug, Default, PartialEq)]
+pub struct Score {
+    pub value: i32,
+}
+
+/// Not `PartialEq`, like `Time`
+#[derive(Resource, Construct, Clone, Debug, Default)]
+pub struct Clock {
+    pub ticks: u32,
+}
+
+#[test]
+fn test_eml_resource_change() {
+    let mut app = App::new();
+    app.add_plugins(FlowPlugin::default());
+    app.init_resource::<Score>();
+    let eml = eml! {
+        resource(score, Score);
+        score.on.change: (e) => {
+            log.text = e.new.value.fmt("{}");
+        };
+        Div [ log: Bold ]
+    };
+    eml.apply(&mut app.world);
+    app.update();
+    app.world.resource_mut::<Score>().value = 3;
+    app.update();
+    assert_eq!("3", bold_text(&mut app.world));
+
+    // changed to the same value
+    set_bold_text(&mut app.world, "x");
+    app.world.resource_mut::<Score>().value = 3;
+    app.update();
+    assert_eq!("x", bold_text(&mut app.world));
+}
+
+#[test]
+fn test_eml_resource_prop_change() {
+    let mut app = App::new();
+    app.add_plugins(FlowPlugin::default());
+    app.init_resource::<Score>();
+    let eml = eml! {
+        resource(score, Score);
+        score.on.change.value: (e) => {
+            log.text = e.new.fmt("value {}");
+        };
+        Div [ log: Bold ]
+    };
+    eml.apply(&mut app.world);
+    app.update();
+    app.world.resource_mut::<Score>().value = 5;
+    app.update();
+    assert_eq!("value 5", bold_text(&mut app.world));
+}
+
+#[test]
+fn test_eml_resource_change_always() {
+    let mut app = App::new();
+    app.add_plugins(FlowPlugin::default());
+    app.init_resource::<Clock>();
+    let eml = eml! {
+        resource(clock, Clock);
+        clock.on.change.always: (e) => {
+            log.text = e.new.ticks.fmt("{}");
+        };
+        Div [ log: Bold ]
+    };
+    eml.apply(&mut app.world);
+    app.update();
+    app.world.resource_mut::<Clock>().ticks = 1;
+    app.update();
+    assert_eq!("1", bold_text(&mut app.world));
+
+    // every change is reported, the values are not compared
+    set_bold_text(&mut app.world, "x");
+    app.world.resource_mut::<Clock>().ticks = 1;
+    app.update();
+    assert_eq!("1", bold_text(&mut app.world));
+}
+
+#[derive(Element)]
+#[construct(Field -> Focusable -> Div)]
+pub struct Field;
+
+impl ElementBuilder for Field {
+    fn build_element(content: Vec<Entity>) -> Blueprint<Self> {
+        blueprint! { Field::Base [[ content ]] }
+    }
+}
+
+#[test]
+fn test_eml_focusable_behavior() {
+    let mut app = App::new();
+    app.add_plugins(FlowPlugin::default());
+    let eml = eml! {
+        Div [
+            field: Field {
+                .tab_index: 1,
+                .on.focus: () => {
+                    log.text = "focus";
+                },
+                .on.key_down: () => {
+                    log.text = "key";
+                }
+            },
+            log: Bold
+        ]
+    };
+    eml.apply(&mut app.world);
+    app.update();
+    let field = app
+        .world
+        .query_filtered::<Entity, With<Field>>()
+        .single(&app.world);
+    assert_eq!(1, app.world.get::<Focusable>(field).unwrap().tab_index);
+    assert!(matches!(
+        app.world.get::<PointerFilter>(field),
+        Some(PointerFilter::Pass)
+    ));
+
+    FocusSignalMarker.emit(&mut app.world, field, default());
+    app.update();
+    assert_eq!("focus", bold_text(&mut app.world));
+
+    KeyDownSignalMarker.emit(&mut app.world, field, KeyCode::A);
+    app.update();
+    assert_eq!("key", bold_text(&mut app.world));
+}
+
+fn press_key(app: &mut App, entity: Entity) {
+    KeyDownSignalMarker.emit(&mut app.world, entity, KeyCode::A);
+    app.update();
+}
+
+fn find_field(world: &mut World, tab_index: i32) -> Entity {
+    world
+        .query::<(Entity, &Focusable)>()
+        .iter(world)
+        .find(|(_, f)| f.tab_index == tab_index)
+        .map(|(e, _)| e)
+        .unwrap()
+}
+
+#[test]
+fn test_eml_hand_once() {
+    let mut app = App::new();
+    app.add_plugins(FlowPlugin::default());
+    let eml = eml! {
+        Div [
+            Field {
+                .on.key_down.once: () => {
+                    log.text = "once";
+                }
+            },
+            log: Bold
+        ]
+    };
+    eml.apply(&mut app.world);
+    app.update();
+    let field = find_field(&mut app.world, 0);
+    press_key(&mut app, field);
+    assert_eq!("once", bold_text(&mut app.world));
+
+    // the hand is removed after the first execution
+    set_bold_text(&mut app.world, "x");
+    press_key(&mut app, field);
+    assert_eq!("x", bold_text(&mut app.world));
+}
+
+#[test]
+fn test_eml_hand_priority() {
+    let mut app = App::new();
+    app.add_plugins(FlowPlugin::default());
+    let eml = eml! {
+        Div [
+            Field {
+                .on.key_down: () => {
+                    log.text = "registered first";
+                },
+                // executed before the former hand, the former one writes last
+                .on.key_down.priority[10]: () => {
+                    log.text = "registered last";
+                }
+            },
+            log: Bold
+        ]
+    };
+    eml.apply(&mut app.world);
+    app.update();
+    let field = find_field(&mut app.world, 0);
+    press_key(&mut app, field);
+    assert_eq!("registered first", bold_text(&mut app.world));
+}
+
+#[test]
+fn test_eml_hand_capture() {
+    let mut app = App::new();
+    app.add_plugins(FlowPlugin::default());
+    let eml = eml! {
+        Div [
+            Field {
+                .tab_index: 1,
+                .on.key_down.capture: () => {
+                    log.text = "parent";
+                }
+                [
+                    Field {
+                        .tab_index: 2,
+                        .on.key_down: () => {
+                            log.text = "child";
+                        }
+                    }
+                ]
+            },
+            log: Bold
+        ]
+    };
+    eml.apply(&mut app.world);
+    app.update();
+    let child = find_field(&mut app.world, 2);
+    press_key(&mut app, child);
+    // the parent captures the signal before the child handles it
+    assert_eq!("child", bold_text(&mut app.world));
+}
+
+#[test]
+fn test_eml_hand_default_action() {
+    let mut app = App::new();
+    app.add_plugins(FlowPlugin::default());
+    let eml = eml! {
+        Div [
+            Field {
+                .tab_index: 1,
+                .on.key_down: () => {
+                    log.text = "parent";
+                }
+                [
+                    Field {
+                        .tab_index: 2,
+                        .on.key_down.default_action: () => {
+                            log.text = "default";
+                        }
+                    }
+                ]
+            },
+            log: Bold
+        ]
+    };
+    eml.apply(&mut app.world);
+    app.update();
+    let child = find_field(&mut app.world, 2);
+    press_key(&mut app, child);
+    // the default action is executed after the signal bubbles up
+    assert_eq!("default", bold_text(&mut app.world));
+}
+
+#[derive(Behavior, Default)]
+pub struct Gauge {
+    pub value: f32,
+}
+
+#[derive(Element)]
+#[construct(Meter -> Gauge -> Div)]
+pub struct Meter;
+
+impl ElementBuilder for Meter {
+    fn build_element(_: Vec<Entity>) -> Blueprint<Self> {
+        blueprint! { Meter::Base }
+    }
+}
+
+fn gauge(world: &mut World, mark: &str) -> Entity {
+    world.resource::<EmlMarks>().get(mark).next().unwrap()
+}
+
+fn set_gauge(world: &mut World, mark: &str, value: f32) {
+    let entity = gauge(world, mark);
+    world.get_mut::<Gauge>(entity).unwrap().value = value;
+}
+
+fn gauge_value(world: &mut World, mark: &str) -> f32 {
+    let entity = gauge(world, mark);
+    world.get::<Gauge>(entity).unwrap().value
+}
+
+fn same_round(a: &f32, b: &f32) -> bool {
+    a.round() == b.round()
+}
+
+#[test]
+fn test_eml_bind_epsilon() {
+    let mut app = App::new();
+    app.add_plugins(FlowPlugin::default());
+    app.init_resource::<EmlMarks>();
+    let eml = eml! {
+        bind(source.value => target.value, epsilon(0.5));
+        Div [
+            source: Meter,
+            target: Meter
+        ]
+    };
+    eml.apply(&mut app.world);
+    app.update();
+    set_gauge(&mut app.world, "source", 0.3);
+    app.update();
+    assert_eq!(0.0, gauge_value(&mut app.world, "target"));
+
+    set_gauge(&mut app.world, "source", 1.0);
+    app.update();
+    assert_eq!(1.0, gauge_value(&mut app.world, "target"));
+}
+
+#[test]
+fn test_eml_bind_compare() {
+    let mut app = App::new();
+    app.add_plugins(FlowPlugin::default());
+    app.init_resource::<EmlMarks>();
+    let eml = eml! {
+        bind(source.value => target.value, compare(same_round));
+        Div [
+            source: Meter,
+            target: Meter
+        ]
+    };
+    eml.apply(&mut app.world);
+    app.update();
+    set_gauge(&mut app.world, "source", 0.4);
+    app.update();
+    assert_eq!(0.0, gauge_value(&mut app.world, "target"));
+
+    set_gauge(&mut app.world, "source", 0.6);
+    app.update();
+    assert_eq!(0.6, gauge_value(&mut app.world, "target"));
+}
+
+#[test]
+fn test_eml_bind_always() {
+    let mut app = App::new();
+    app.add_plugins(FlowPlugin::default());
+    app.init_resource::<EmlMarks>();
+    let eml = eml! {
+        bind(source.value => forced.value, always);
+        bind(source.value => compared.value);
+        Div [
+            source: Meter,
+            forced: Meter,
+            compared: Meter
+        ]
+    };
+    eml.apply(&mut app.world);
+    app.update();
+    let last_changed = |world: &mut World, mark: &str| {
+        let entity = gauge(world, mark);
+        world.entity(entity).get_ref::<Gauge>().unwrap().last_changed()
+    };
+    let forced = last_changed(&mut app.world, "forced");
+    let compared = last_changed(&mut app.world, "compared");
+
+    // the same value is written again only with `always`
+    set_gauge(&mut app.world, "source", 0.0);
+    app.update();
+    assert_ne!(forced, last_changed(&mut app.world, "forced"));
+    assert_eq!(compared, last_changed(&mut app.world, "compared"));
+    assert_eq!(0.0, gauge_value(&mut app.world, "forced"));
+}
+
+#[test]
+fn test_eml_bind_indexed() {
+    let mut app = App::new();
+    app.add_plugins(FlowPlugin::default());
+    let eml = eml! {
+        bind(list.items[1] => second.text);
+        bind(list.items[5] => missing.text);
+        bind(source.text => list.items[0]);
+        Div [
+            list: List,
+            second: Label { .text: "second" },
+            missing: Label { .text: "missing" },
+            source: Bold { .text: "c" }
+        ]
+    };
+    eml.apply(&mut app.world);
+    app.update();
+    let world = &mut app.world;
+    assert_eq!(
+        vec!["c".to_string(), "b".to_string()],
+        world.query::<&Inventory>().single(world).items
+    );
+    // the out of range item doesn't write the target
+    assert_eq!(
+        vec!["b".to_string(), "c".to_string(), "missing".to_string()],
+        texts(world)
+    );
+}
+
+#[derive(Event, Construct, Clone, Default)]
+pub struct Scored {
+    pub value: i32,
+}
+
+#[test]
+fn test_eml_bind_events() {
+    let mut app = App::new();
+    app.add_plugins(FlowPlugin::default());
+    app.add_event::<Scored>();
+    let eml = eml! {
+        events<Scored>(scored);
+        bind(scored.last.value.fmt("{}") => declared.text);
+        bind(events<Scored>.last.value.fmt("inline {}") => log.text);
+        Div [
+            declared: Label,
+            log: Bold
+        ]
+    };
+    eml.apply(&mut app.world);
+    app.update();
+    app.world.send_event(Scored { value: 1 });
+    app.world.send_event(Scored { value: 2 });
+    app.update();
+    assert_eq!("inline 2", bold_text(&mut app.world));
+    assert!(texts(&mut app.world).contains(&"2".to_string()));
+}
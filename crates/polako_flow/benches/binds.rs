@@ -0,0 +1,82 @@
+use bevy::prelude::*;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use polako_constructivism::*;
+use polako_flow::*;
+
+const BINDS: [usize; 3] = [1_000, 10_000, 100_000];
+
+/// `binds` sources with the `Text`, every source is bound to its own target.
+fn setup(binds: usize) -> (App, Vec<Entity>) {
+    let mut app = App::new();
+    app.add_plugins(FlowPlugin::default());
+    let mut sources = Vec::with_capacity(binds);
+    for idx in 0..binds {
+        let text = Text::from_section(format!("{idx}"), default());
+        let source = app.world.spawn(text.clone()).id();
+        let target = app.world.spawn(text).id();
+        app.world.bind_component_to_component(
+            source.get(prop!(Text.text)),
+            target.set(prop!(Text.text)),
+        );
+        sources.push(source);
+    }
+    // register the flow systems and settle the initial values
+    app.update();
+    app.update();
+    (app, sources)
+}
+
+fn idle_frame(c: &mut Criterion) {
+    let mut group = c.benchmark_group("idle_frame");
+    for binds in BINDS {
+        let (mut app, _) = setup(binds);
+        group.bench_with_input(BenchmarkId::from_parameter(binds), &binds, |b, _| {
+            b.iter(|| app.update())
+        });
+    }
+    group.finish();
+}
+
+/// 1% of the sources change every frame.
+fn sparse_changes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sparse_changes");
+    for binds in BINDS {
+        let (mut app, sources) = setup(binds);
+        let mut frame = 0usize;
+        group.bench_with_input(BenchmarkId::from_parameter(binds), &binds, |b, _| {
+            b.iter(|| {
+                frame += 1;
+                for source in sources.iter().skip(frame % 100).step_by(100) {
+                    let mut text = app.world.get_mut::<Text>(*source).unwrap();
+                    text.sections[0].value = format!("{frame}");
+                }
+                app.update()
+            })
+        });
+    }
+    group.finish();
+}
+
+/// Every source changes every frame.
+fn dense_changes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dense_changes");
+    group.sample_size(10);
+    for binds in BINDS {
+        let (mut app, sources) = setup(binds);
+        let mut frame = 0usize;
+        group.bench_with_input(BenchmarkId::from_parameter(binds), &binds, |b, _| {
+            b.iter(|| {
+                frame += 1;
+                for source in sources.iter() {
+                    let mut text = app.world.get_mut::<Text>(*source).unwrap();
+                    text.sections[0].value = format!("{frame}");
+                }
+                app.update()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, idle_frame, sparse_changes, dense_changes);
+criterion_main!(benches);
@@ -5,7 +5,7 @@ use polako_flow::*;
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
-        .add_plugins(FlowPlugin)
+        .add_plugins(FlowPlugin::default())
         .add_systems(Startup, setup)
         .run();
 }
@@ -0,0 +1,308 @@
+use std::marker::PhantomData;
+
+use bevy::{
+    ecs::system::{StaticSystemParam, SystemParam},
+    prelude::*,
+    utils::HashSet,
+};
+use polako_constructivism::*;
+
+use crate::{
+    Bindable, ChangedEntities, Comparator, ComponentReader, EntityFlow, FlowItem, FlowPhases,
+    FlowResource, HandId, Reader, ResourceReader, Signal,
+};
+
+/// Emits when the watched prop changes:
+/// ```ignore
+/// .on.change.text: (e) => {
+///     info("{:?} -> {}", e.old, e.new);
+/// }
+/// ```
+/// The `entity` is the entity the hand is assigned to. If the prop didn't
+/// exist when the hand was assigned, the first value is the change with
+/// the `old` set to `None`.
+#[derive(Event, Clone, Debug)]
+pub struct ChangeSignal<V: Bindable> {
+    pub entity: Entity,
+    pub old: Option<V>,
+    pub new: V,
+    hand: HandId,
+}
+
+impl<V: Bindable> Signal for ChangeSignal<V> {
+    type Event = Self;
+    type Args = ();
+    type Descriptor = ();
+    fn filter(event: &Self::Event) -> Option<Entity> {
+        Some(event.entity)
+    }
+    fn hand(event: &Self::Event) -> Option<HandId> {
+        Some(event.hand)
+    }
+}
+
+impl<V: Bindable> ChangeSignal<V> {
+    pub fn getters(&self) -> &'static ChangeSignalGetters<V> {
+        &ChangeSignalGetters(PhantomData)
+    }
+}
+
+pub struct ChangeSignalGetters<V>(PhantomData<V>);
+
+impl<V: Bindable> ChangeSignalGetters<V> {
+    pub fn entity<'a>(&self, host: &'a ChangeSignal<V>) -> Value<'a, Entity> {
+        Value::Val(host.entity)
+    }
+    pub fn old<'a>(&self, host: &'a ChangeSignal<V>) -> Value<'a, Option<V>> {
+        Value::Val(host.old.clone())
+    }
+    pub fn new<'a>(&self, host: &'a ChangeSignal<V>) -> Value<'a, V> {
+        Value::Val(host.new.clone())
+    }
+}
+
+/// `.on.change.text` part, watches the prop of the entity the hand is assigned to.
+pub struct PropChange<C: Component, V: Bindable> {
+    reader: Reader<C, V>,
+}
+
+impl<C: Component, V: Bindable + PartialEq> PropChange<C, V> {
+    pub fn new(reader: impl Into<Reader<C, V>>) -> Self {
+        PropChange {
+            reader: reader.into(),
+        }
+    }
+
+    pub fn assign<
+        'w,
+        S: SystemParam + 'static,
+        F: Fn(&ChangeSignal<V>, &mut StaticSystemParam<S>) + 'static,
+    >(
+        &self,
+        entity: &mut EntityWorldMut<'w>,
+        func: F,
+    ) -> HandId {
+        let source = ComponentReader {
+            entity: entity.id(),
+            reader: self.reader.clone(),
+            prop: "",
+            isolated: false,
+        };
+        entity.on_component_change(source, func)
+    }
+}
+
+/// `time.on.change.elapsed` part, watches the prop of the resource.
+pub struct ResourceChange<R: Resource, V: Bindable> {
+    reader: Reader<R, V>,
+    compare: Comparator<V>,
+}
+
+impl<R: Resource, V: Bindable + PartialEq> ResourceChange<R, V> {
+    pub fn new(reader: impl Into<Reader<R, V>>) -> Self {
+        ResourceChange {
+            reader: reader.into(),
+            compare: Comparator::eq(),
+        }
+    }
+}
+
+impl<R: Resource, V: Bindable> ResourceChange<R, V> {
+    /// Watches the prop using `compare`, see [`ResourceChange::resource_with`].
+    pub fn new_with(reader: impl Into<Reader<R, V>>, compare: Comparator<V>) -> Self {
+        ResourceChange {
+            reader: reader.into(),
+            compare,
+        }
+    }
+
+    pub fn assign<
+        'w,
+        S: SystemParam + 'static,
+        F: Fn(&ChangeSignal<V>, &mut StaticSystemParam<S>) + 'static,
+    >(
+        &self,
+        entity: &mut EntityWorldMut<'w>,
+        func: F,
+    ) -> HandId {
+        let source = ResourceReader::from(self.reader.clone());
+        entity.on_resource_change_with(source, self.compare, func)
+    }
+}
+
+impl<R: Resource + Bindable + PartialEq> ResourceChange<R, R> {
+    /// Watches the resource itself: `score.on.change`. The signal is emitted
+    /// when the resource is changed to the different value.
+    pub fn resource() -> Self {
+        Self::resource_with(Comparator::eq())
+    }
+}
+
+impl<R: Resource + Bindable> ResourceChange<R, R> {
+    /// Watches the resource itself using `compare`. The resources without
+    /// `PartialEq` (like `Time`) could be watched with [`Comparator::Always`],
+    /// the signal is emitted every time the resource is changed then.
+    pub fn resource_with(compare: Comparator<R>) -> Self {
+        ResourceChange {
+            reader: Reader::Func(read_resource::<R>),
+            compare,
+        }
+    }
+}
+
+fn read_resource<R: Bindable>(res: &R) -> Value<R> {
+    Value::Val(res.clone())
+}
+
+pub(crate) struct Watch<H, V: Bindable> {
+    /// The flow phase of the watching hand.
+    pub(crate) phase: &'static str,
+    pub(crate) reader: Reader<H, V>,
+    pub(crate) compare: Comparator<V>,
+    pub(crate) last: Option<V>,
+    pub(crate) host: Entity,
+    pub(crate) hand: HandId,
+}
+
+impl<H, V: Bindable> Watch<H, V> {
+    /// Returns the signal if the value differs from the last seen one.
+    fn check(&mut self, host: &H) -> Option<ChangeSignal<V>> {
+        let value = self.reader.read(host)?.get();
+        // `None` if the prop didn't exist when the hand was assigned
+        let old = self.last.replace(value.clone());
+        if old.as_ref().map_or(false, |old| self.compare.same(old, &value)) {
+            return None;
+        }
+        Some(ChangeSignal {
+            entity: self.host,
+            old,
+            new: value,
+            hand: self.hand,
+        })
+    }
+}
+
+#[derive(Component)]
+pub(crate) struct ComponentWatchers<C: Component, V: Bindable>(pub(crate) Vec<Watch<C, V>>);
+
+#[derive(Resource)]
+pub(crate) struct ResourceWatchers<R: Resource, V: Bindable>(pub(crate) Vec<Watch<R, V>>);
+
+pub(crate) fn watch_component_changes<C: Component, V: Bindable>(
+    mut components: Query<(&mut ComponentWatchers<C, V>, &C)>,
+    changed: Res<ChangedEntities<C>>,
+    mut signals: EventWriter<ChangeSignal<V>>,
+    phases: Res<FlowPhases>,
+) {
+    let phase = phases.active();
+    let mut iter = components.iter_many_mut(changed.iter());
+    while let Some((mut watchers, component)) = iter.fetch_next() {
+        for watch in watchers.0.iter_mut().filter(|w| w.phase == phase) {
+            if let Some(signal) = watch.check(component) {
+                signals.send(signal);
+            }
+        }
+    }
+}
+
+pub(crate) fn watch_resource_changes<R: Resource, V: Bindable>(
+    res: Option<Res<R>>,
+    mut watchers: ResMut<ResourceWatchers<R, V>>,
+    mut signals: EventWriter<ChangeSignal<V>>,
+    phases: Res<FlowPhases>,
+) {
+    let Some(res) = res else {
+        return;
+    };
+    if !res.is_changed() {
+        return;
+    }
+    let phase = phases.active();
+    for watch in watchers.0.iter_mut().filter(|w| w.phase == phase) {
+        if let Some(signal) = watch.check(&res) {
+            signals.send(signal);
+        }
+    }
+}
+
+pub(crate) fn cleanup_component_watchers<C: Component, V: Bindable>(
+    mut watchers: Query<&mut ComponentWatchers<C, V>>,
+    mut removals: RemovedComponents<FlowItem>,
+) {
+    let removed = removals.read().collect::<HashSet<_>>();
+    if removed.is_empty() {
+        return;
+    }
+    for mut watchers in watchers.iter_mut() {
+        watchers.0.retain(|w| !removed.contains(&w.host));
+    }
+}
+
+pub(crate) fn cleanup_resource_watchers<R: Resource, V: Bindable>(
+    mut watchers: ResMut<ResourceWatchers<R, V>>,
+    mut removals: RemovedComponents<FlowItem>,
+) {
+    let removed = removals.read().collect::<HashSet<_>>();
+    if !removed.is_empty() {
+        watchers.0.retain(|w| !removed.contains(&w.host));
+    }
+}
+
+pub(crate) fn add_component_watch<C: Component, V: Bindable>(
+    world: &mut World,
+    source: ComponentReader<C, V>,
+    compare: Comparator<V>,
+    host: Entity,
+    hand: HandId,
+) {
+    let last = world
+        .get::<C>(source.entity)
+        .and_then(|c| source.reader.read(c))
+        .map(|v| v.get());
+    let watch = Watch {
+        phase: world.resource::<FlowPhases>().active(),
+        reader: source.reader,
+        compare,
+        last,
+        host,
+        hand,
+    };
+    if let Some(mut watchers) = world.get_mut::<ComponentWatchers<C, V>>(source.entity) {
+        watchers.0.push(watch);
+    } else {
+        world
+            .entity_mut(source.entity)
+            .insert(ComponentWatchers(vec![watch]));
+    }
+    world
+        .resource::<FlowResource>()
+        .register_component_watch_systems::<C, V>();
+}
+
+pub(crate) fn add_resource_watch<R: Resource, V: Bindable>(
+    world: &mut World,
+    source: ResourceReader<R, V>,
+    compare: Comparator<V>,
+    host: Entity,
+    hand: HandId,
+) {
+    let last = world
+        .get_resource::<R>()
+        .and_then(|r| source.reader.read(r))
+        .map(|v| v.get());
+    let watch = Watch {
+        phase: world.resource::<FlowPhases>().active(),
+        reader: source.reader,
+        compare,
+        last,
+        host,
+        hand,
+    };
+    world
+        .get_resource_or_insert_with(|| ResourceWatchers::<R, V>(vec![]))
+        .0
+        .push(watch);
+    world
+        .resource::<FlowResource>()
+        .register_resource_watch_systems::<R, V>();
+}
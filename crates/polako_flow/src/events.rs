@@ -0,0 +1,192 @@
+use std::{any::type_name, rc::Rc};
+
+use bevy::{
+    prelude::*,
+    utils::{tracing::trace_span, HashSet},
+};
+use polako_constructivism::*;
+
+use crate::{
+    ApplyChange, BindOrder, Bindable, Changes, Comparator, FlowGraph, FlowGraphBind,
+    FlowGraphSource, FlowItem, FlowPhases, FlowStats, Reader, Writer,
+};
+
+/// Binds the value taken from the `Events<E>` sent since the last flow loop:
+/// ```ignore
+/// world.bind_events_to_component(
+///     EventsReader::last(prop!(ScoreChanged.value)),
+///     label.set(prop!(Label.text)),
+/// );
+/// world.bind_events_to_component(
+///     EventsReader::fold(0, |total, e: &ScoreChanged| total + e.delta),
+///     total.set(prop!(Counter.value)),
+/// );
+/// ```
+/// In `eml!`: `bind(events<ScoreChanged>.last.value => label.text)`.
+/// The target is written only if there were events. The missing `Events<E>`
+/// is inserted by the bind, but only `app.add_event::<E>()` drops the old events.
+pub struct EventsReader<E: Event, V: Bindable> {
+    value: EventValue<E, V>,
+    prop: &'static str,
+}
+
+enum EventValue<E, V: Bindable> {
+    Last(Reader<E, V>),
+    Fold(V, Rc<dyn Fn(&V, &E) -> V>),
+}
+
+unsafe impl<E: Event, V: Bindable> Send for EventsReader<E, V> {}
+unsafe impl<E: Event, V: Bindable> Sync for EventsReader<E, V> {}
+
+impl<E: Event, V: Bindable> EventsReader<E, V> {
+    /// Reads the prop of the latest event.
+    pub fn last(reader: impl Into<Reader<E, V>>) -> Self {
+        EventsReader {
+            value: EventValue::Last(reader.into()),
+            prop: "",
+        }
+    }
+
+    /// Folds every event into the value starting with `init`.
+    pub fn fold(init: V, fold: impl Fn(&V, &E) -> V + 'static) -> Self {
+        EventsReader {
+            value: EventValue::Fold(init, Rc::new(fold)),
+            prop: "",
+        }
+    }
+
+    /// Labels the source prop, see [`crate::ComponentReader::named`].
+    pub fn named(mut self, prop: &'static str) -> Self {
+        self.prop = prop;
+        self
+    }
+}
+
+impl<E: Event + Bindable> EventsReader<E, E> {
+    /// The latest event itself: `events<ScoreChanged>.last`
+    pub fn last_event() -> Self {
+        EventsReader::last(Reader::Func(clone_event::<E>))
+    }
+}
+
+fn clone_event<E: Bindable>(event: &E) -> Value<E> {
+    Value::Val(event.clone())
+}
+
+pub(crate) struct EventBindSource<E, T, V: Bindable> {
+    phase: &'static str,
+    target: Entity,
+    value: EventValue<E, V>,
+    writer: Writer<T, V>,
+    compare: Comparator<V>,
+    from_prop: &'static str,
+    to_prop: &'static str,
+    order: BindOrder,
+}
+
+#[derive(Resource)]
+pub(crate) struct EventBindSources<E: Event, T: Component, V: Bindable>(
+    Vec<EventBindSource<E, T, V>>,
+);
+unsafe impl<E: Event, T: Component, V: Bindable> Send for EventBindSources<E, T, V> {}
+unsafe impl<E: Event, T: Component, V: Bindable> Sync for EventBindSources<E, T, V> {}
+
+pub(crate) fn add_event_bind<E: Event, T: Component, V: Bindable>(
+    world: &mut World,
+    from: EventsReader<E, V>,
+    to: crate::ComponentWriter<T, V>,
+    compare: Comparator<V>,
+) {
+    let source = EventBindSource {
+        phase: world.resource::<FlowPhases>().active(),
+        target: to.entity,
+        value: from.value,
+        writer: to.writer,
+        compare,
+        from_prop: from.prop,
+        to_prop: to.prop,
+        order: BindOrder::next(to.priority),
+    };
+    world.entity_mut(source.target).insert(FlowItem);
+    world
+        .get_resource_or_insert_with(|| EventBindSources::<E, T, V>(vec![]))
+        .0
+        .push(source);
+}
+
+pub(crate) fn cleanup_event_readers<E: Event, T: Component, V: Bindable>(
+    mut sources: ResMut<EventBindSources<E, T, V>>,
+    mut removals: RemovedComponents<FlowItem>,
+) {
+    let removed = removals.read().collect::<HashSet<_>>();
+    if !removed.is_empty() {
+        sources.0.retain(|s| !removed.contains(&s.target));
+    }
+}
+
+pub(crate) fn read_event_changes<E: Event, T: Component, V: Bindable>(
+    mut events: EventReader<E>,
+    mut sources: ResMut<EventBindSources<E, T, V>>,
+    changes: Changes<T, V>,
+    phases: Res<FlowPhases>,
+    stats: Res<FlowStats>,
+) {
+    if events.is_empty() {
+        return;
+    }
+    let events = events.read().collect::<Vec<_>>();
+    let phase = phases.active();
+    let _span = trace_span!("binds", source = type_name::<E>(), target = type_name::<T>()).entered();
+    for source in sources.0.iter_mut().filter(|s| s.phase == phase) {
+        let _span = trace_span!(
+            "bind",
+            from = source.from_prop,
+            target = ?source.target,
+            to = source.to_prop
+        )
+        .entered();
+        stats.bind_evaluated();
+        let value = match &mut source.value {
+            EventValue::Last(reader) => {
+                let Some(value) = events.last().and_then(|e| reader.read(e)) else {
+                    continue;
+                };
+                value.get()
+            }
+            EventValue::Fold(value, fold) => {
+                for event in events.iter() {
+                    *value = fold(value, event);
+                }
+                value.clone()
+            }
+        };
+        changes.send(ApplyChange {
+            value,
+            target: source.target,
+            writer: source.writer.clone(),
+            compare: source.compare,
+            order: source.order,
+            prop: source.to_prop,
+        })
+    }
+}
+
+pub(crate) fn inspect_event_binds<E: Event, T: Component, V: Bindable>(
+    world: &World,
+    graph: &mut FlowGraph,
+) {
+    let Some(sources) = world.get_resource::<EventBindSources<E, T, V>>() else {
+        return;
+    };
+    for source in sources.0.iter() {
+        graph.binds.push(FlowGraphBind {
+            source: FlowGraphSource::Resource(type_name::<Events<E>>()),
+            source_type: type_name::<E>(),
+            source_prop: source.from_prop,
+            target: source.target,
+            target_type: type_name::<T>(),
+            target_prop: source.to_prop,
+            value_type: type_name::<V>(),
+        })
+    }
+}
@@ -0,0 +1,173 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use bevy::{
+    ecs::system::{Command, Deferred},
+    prelude::*,
+};
+use polako_constructivism::Singleton;
+
+use crate::{FlowLoopControl, Signal};
+
+/// Emits for the element and every of its descendants before the element
+/// leaves the world:
+/// - before the despawn started with [`crate::WorldFlow::exit`] or [`Exit`],
+///   the hands may [`ExitSignal::defer`] the despawn;
+/// - when the element is removed from its parent, the element stays alive,
+///   so there is nothing to defer.
+///
+/// Bevy can't notify before the plain `despawn_recursive`, despawn the
+/// elements with the exit hands using `Exit`.
+/// ```ignore
+/// .on.exit: (e) => {
+///     e.defer();
+///     panel.closing = true;
+/// }
+/// .on.closed: () => {
+///     panel.complete_exit();
+/// }
+/// ```
+#[derive(Event, Clone)]
+pub struct ExitSignal {
+    pub entity: Entity,
+    /// The entity being despawned: the `entity` itself or one of its ancestors.
+    pub root: Entity,
+    holds: Arc<AtomicUsize>,
+}
+
+impl ExitSignal {
+    /// Postpones the despawn of the `root` until the [`CompleteExit`] is applied.
+    /// Every `defer()` requires its own `CompleteExit`.
+    pub fn defer(&self) {
+        self.holds.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl Signal for ExitSignal {
+    type Event = Self;
+    type Args = ();
+    type Descriptor = ExitSignalDescriptor;
+    fn filter(event: &Self::Event) -> Option<Entity> {
+        Some(event.entity)
+    }
+}
+
+pub struct ExitSignalDescriptor;
+impl Singleton for ExitSignalDescriptor {
+    fn instance() -> &'static Self {
+        &ExitSignalDescriptor
+    }
+}
+
+/// Inserted to the entity when the exit starts, the entity is despawned
+/// when there are no deferred exits left.
+#[derive(Component)]
+pub struct Exiting {
+    holds: Arc<AtomicUsize>,
+}
+
+impl Exiting {
+    /// The number of the hands which deferred the despawn.
+    pub fn holds(&self) -> usize {
+        self.holds.load(Ordering::Relaxed)
+    }
+}
+
+/// Emits the [`ExitSignal`] and despawns the entity recursively.
+pub struct Exit(pub Entity);
+
+impl Command for Exit {
+    fn apply(self, world: &mut World) {
+        let root = self.0;
+        if world.get_entity(root).is_none() || world.get::<Exiting>(root).is_some() {
+            return;
+        }
+        let holds = Arc::new(AtomicUsize::new(0));
+        world.entity_mut(root).insert(Exiting {
+            holds: holds.clone(),
+        });
+        let exiting = descendants(root, |e| world.get::<Children>(e));
+        let mut events = world.get_resource_or_insert_with(Events::<ExitSignal>::default);
+        for entity in exiting {
+            events.send(ExitSignal {
+                entity,
+                root,
+                holds: holds.clone(),
+            });
+        }
+    }
+}
+
+/// The `root` and its descendants, parents go first.
+fn descendants<'a>(root: Entity, children: impl Fn(Entity) -> Option<&'a Children>) -> Vec<Entity> {
+    let mut entities = vec![root];
+    let mut idx = 0;
+    while idx < entities.len() {
+        if let Some(children) = children(entities[idx]) {
+            entities.extend(children.iter().copied());
+        }
+        idx += 1;
+    }
+    entities
+}
+
+/// Releases the exit deferred with [`ExitSignal::defer`].
+pub struct CompleteExit(pub Entity);
+
+impl Command for CompleteExit {
+    fn apply(self, world: &mut World) {
+        let Some(exiting) = world.get::<Exiting>(self.0) else {
+            return;
+        };
+        let released = exiting
+            .holds
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |h| h.checked_sub(1))
+            .map(|h| h == 1)
+            .unwrap_or(false);
+        if released {
+            world.entity_mut(self.0).despawn_recursive();
+        }
+    }
+}
+
+/// The exits read during the previous iteration were dispatched to the exit
+/// hands since then, so the hands had the chance to defer them.
+pub(crate) fn despawn_exited(
+    mut commands: Commands,
+    mut exits: EventReader<ExitSignal>,
+    exiting: Query<&Exiting>,
+    mut pending: Local<Vec<Entity>>,
+    mut flow: Deferred<FlowLoopControl>,
+) {
+    for root in pending.drain(..) {
+        if exiting.get(root).map(|e| e.holds() == 0).unwrap_or(false) {
+            commands.entity(root).despawn_recursive();
+        }
+    }
+    for exit in exits.read().filter(|e| e.entity == e.root) {
+        pending.push(exit.root);
+        flow.repeat();
+    }
+}
+
+/// Emits the [`ExitSignal`] for the elements removed from their parents.
+pub(crate) fn exit_removed_children(
+    mut removals: RemovedComponents<Parent>,
+    orphans: Query<(), (Without<Parent>, Without<Exiting>)>,
+    children: Query<&Children>,
+    mut events: EventWriter<ExitSignal>,
+) {
+    for root in removals.read().filter(|e| orphans.contains(*e)) {
+        // nothing to defer: the element is not despawned
+        let holds = Arc::new(AtomicUsize::new(0));
+        for entity in descendants(root, |e| children.get(e).ok()) {
+            events.send(ExitSignal {
+                entity,
+                root,
+                holds: holds.clone(),
+            });
+        }
+    }
+}
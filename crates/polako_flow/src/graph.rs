@@ -0,0 +1,312 @@
+use std::{any::type_name, fmt::Write};
+
+use bevy::{ecs::system::SystemParam, prelude::*, utils::HashMap};
+
+use crate::{Bindable, ComponentBindSources, FlowResource, Hands, ResourceBindSources};
+
+/// Where the bound value comes from.
+#[derive(Debug, Clone)]
+pub enum FlowGraphSource {
+    Entity(Entity),
+    Resource(&'static str),
+}
+
+#[derive(Debug, Clone)]
+pub struct FlowGraphBind {
+    pub source: FlowGraphSource,
+    pub source_type: &'static str,
+    pub source_prop: &'static str,
+    pub target: Entity,
+    pub target_type: &'static str,
+    pub target_prop: &'static str,
+    pub value_type: &'static str,
+}
+
+#[derive(Debug, Clone)]
+pub struct FlowGraphHand {
+    pub entity: Entity,
+    pub signal: &'static str,
+    pub params: &'static str,
+    pub handlers: usize,
+}
+
+/// The snapshot of every bind and hand registered in the flow.
+/// Use it to find out why some prop doesn't update:
+/// ```ignore
+/// let graph = FlowGraph::snapshot(&world);
+/// std::fs::write("flow.dot", graph.to_dot()).unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FlowGraph {
+    pub binds: Vec<FlowGraphBind>,
+    pub hands: Vec<FlowGraphHand>,
+    names: HashMap<Entity, String>,
+}
+
+impl FlowGraph {
+    pub fn snapshot(world: &World) -> FlowGraph {
+        let mut graph = FlowGraph::default();
+        let Some(flow) = world.get_resource::<FlowResource>() else {
+            return graph;
+        };
+        for inspect in flow.registry.inspectors.borrow().iter() {
+            inspect(world, &mut graph);
+        }
+        graph.binds.sort_by_key(|b| {
+            let source = match b.source {
+                FlowGraphSource::Entity(e) => e.to_bits(),
+                FlowGraphSource::Resource(_) => 0,
+            };
+            (b.target.to_bits(), source)
+        });
+        graph.hands.sort_by_key(|h| h.entity.to_bits());
+        let entities = graph
+            .binds
+            .iter()
+            .flat_map(|b| match b.source {
+                FlowGraphSource::Entity(e) => [Some(e), Some(b.target)],
+                FlowGraphSource::Resource(_) => [None, Some(b.target)],
+            })
+            .flatten()
+            .chain(graph.hands.iter().map(|h| h.entity))
+            .collect::<Vec<_>>();
+        for entity in entities {
+            if let Some(name) = world.get::<Name>(entity) {
+                graph.names.insert(entity, name.as_str().to_string());
+            }
+        }
+        graph
+    }
+
+    /// Returns the `Name` of the entity if it has one, `Entity` debug representation otherwise.
+    pub fn label(&self, entity: Entity) -> String {
+        if let Some(name) = self.names.get(&entity) {
+            format!("{name} ({entity:?})")
+        } else {
+            format!("{entity:?}")
+        }
+    }
+
+    /// Exports the graph in Graphviz DOT format.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::new();
+        let mut nodes = vec![];
+        let node = |source: &FlowGraphSource| match source {
+            FlowGraphSource::Entity(e) => format!("{e:?}"),
+            FlowGraphSource::Resource(r) => format!("res:{r}"),
+        };
+        writeln!(out, "digraph flow {{").unwrap();
+        writeln!(out, "    rankdir=LR;").unwrap();
+        for bind in self.binds.iter() {
+            let source = node(&bind.source);
+            let target = format!("{:?}", bind.target);
+            if !nodes.contains(&source) {
+                let (label, shape) = match bind.source {
+                    FlowGraphSource::Entity(e) => (self.label(e), "ellipse"),
+                    FlowGraphSource::Resource(r) => (r.to_string(), "box"),
+                };
+                writeln!(
+                    out,
+                    "    \"{}\" [label=\"{}\", shape={shape}];",
+                    escape_dot(&source),
+                    escape_dot(&label)
+                )
+                .unwrap();
+                nodes.push(source.clone());
+            }
+            if !nodes.contains(&target) {
+                writeln!(
+                    out,
+                    "    \"{}\" [label=\"{}\"];",
+                    escape_dot(&target),
+                    escape_dot(&self.label(bind.target))
+                )
+                .unwrap();
+                nodes.push(target.clone());
+            }
+            writeln!(
+                out,
+                "    \"{}\" -> \"{}\" [label=\"{} => {}: {}\"];",
+                escape_dot(&source),
+                escape_dot(&target),
+                escape_dot(prop(bind.source_prop)),
+                escape_dot(prop(bind.target_prop)),
+                escape_dot(bind.value_type),
+            )
+            .unwrap();
+        }
+        for (idx, hand) in self.hands.iter().enumerate() {
+            let entity = format!("{:?}", hand.entity);
+            if !nodes.contains(&entity) {
+                writeln!(
+                    out,
+                    "    \"{}\" [label=\"{}\"];",
+                    escape_dot(&entity),
+                    escape_dot(&self.label(hand.entity))
+                )
+                .unwrap();
+                nodes.push(entity.clone());
+            }
+            writeln!(
+                out,
+                "    \"hand{idx}\" [label=\"on {} x{}\\n{}\", shape=note];",
+                escape_dot(hand.signal),
+                hand.handlers,
+                escape_dot(hand.params),
+            )
+            .unwrap();
+            writeln!(
+                out,
+                "    \"{}\" -> \"hand{idx}\" [style=dashed];",
+                escape_dot(&entity)
+            )
+            .unwrap();
+        }
+        writeln!(out, "}}").unwrap();
+        out
+    }
+
+    /// Exports the graph as JSON object with `binds` and `hands` arrays.
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        out.push_str("{\"binds\":[");
+        for (idx, bind) in self.binds.iter().enumerate() {
+            if idx > 0 {
+                out.push(',');
+            }
+            let (source_kind, source) = match bind.source {
+                FlowGraphSource::Entity(e) => ("entity", self.label(e)),
+                FlowGraphSource::Resource(r) => ("resource", r.to_string()),
+            };
+            write!(
+                out,
+                "{{\"source_kind\":\"{source_kind}\",\"source\":\"{}\",\"source_type\":\"{}\",\
+                \"source_prop\":\"{}\",\"target\":\"{}\",\"target_type\":\"{}\",\
+                \"target_prop\":\"{}\",\"value_type\":\"{}\"}}",
+                escape_json(&source),
+                escape_json(bind.source_type),
+                escape_json(bind.source_prop),
+                escape_json(&self.label(bind.target)),
+                escape_json(bind.target_type),
+                escape_json(bind.target_prop),
+                escape_json(bind.value_type),
+            )
+            .unwrap();
+        }
+        out.push_str("],\"hands\":[");
+        for (idx, hand) in self.hands.iter().enumerate() {
+            if idx > 0 {
+                out.push(',');
+            }
+            write!(
+                out,
+                "{{\"entity\":\"{}\",\"signal\":\"{}\",\"params\":\"{}\",\"handlers\":{}}}",
+                escape_json(&self.label(hand.entity)),
+                escape_json(hand.signal),
+                escape_json(hand.params),
+                hand.handlers,
+            )
+            .unwrap();
+        }
+        out.push_str("]}");
+        out
+    }
+}
+
+fn prop(name: &'static str) -> &'static str {
+    if name.is_empty() {
+        "?"
+    } else {
+        name
+    }
+}
+
+/// Escapes the DOT quoted string, the other control characters are
+/// replaced with spaces: DOT has no escapes for them.
+fn escape_dot(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            ch if ch.is_control() => out.push(' '),
+            ch => out.push(ch),
+        }
+    }
+    out
+}
+
+/// Escapes the JSON string, the control characters are `\uXXXX`.
+fn escape_json(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            ch if ch < '\u{20}' => write!(out, "\\u{:04x}", ch as u32).unwrap(),
+            ch => out.push(ch),
+        }
+    }
+    out
+}
+
+pub(crate) fn inspect_component_binds<S: Component, T: Component, V: Bindable>(
+    world: &World,
+    graph: &mut FlowGraph,
+) {
+    for entity in world.iter_entities() {
+        let Some(sources) = entity.get::<ComponentBindSources<S, T, V>>() else {
+            continue;
+        };
+        for source in sources.0.values() {
+            graph.binds.push(FlowGraphBind {
+                source: FlowGraphSource::Entity(entity.id()),
+                source_type: type_name::<S>(),
+                source_prop: source.from_prop,
+                target: source.target,
+                target_type: type_name::<T>(),
+                target_prop: source.to_prop,
+                value_type: type_name::<V>(),
+            })
+        }
+    }
+}
+
+pub(crate) fn inspect_resource_binds<S: Resource, T: Component, V: Bindable>(
+    world: &World,
+    graph: &mut FlowGraph,
+) {
+    let Some(sources) = world.get_resource::<ResourceBindSources<S, T, V>>() else {
+        return;
+    };
+    for source in sources.0.values() {
+        graph.binds.push(FlowGraphBind {
+            source: FlowGraphSource::Resource(type_name::<S>()),
+            source_type: type_name::<S>(),
+            source_prop: source.from_prop,
+            target: source.target,
+            target_type: type_name::<T>(),
+            target_prop: source.to_prop,
+            value_type: type_name::<V>(),
+        })
+    }
+}
+
+pub(crate) fn inspect_hands<E: Event, S: SystemParam + 'static>(
+    world: &World,
+    graph: &mut FlowGraph,
+) {
+    for entity in world.iter_entities() {
+        let Some(hands) = entity.get::<Hands<E, S>>() else {
+            continue;
+        };
+        graph.hands.push(FlowGraphHand {
+            entity: entity.id(),
+            signal: type_name::<E>(),
+            params: type_name::<S>(),
+            handlers: hands.len(),
+        })
+    }
+}
@@ -1,16 +1,22 @@
-use super::Signal;
+use std::marker::PhantomData;
+
+use super::{EntityFlow, Signal};
 use bevy::prelude::*;
 use polako_constructivism::Singleton;
-use polako_input::{PointerInput, PointerInputData, PointerInputPosition};
+use polako_input::{
+    DragDropData, DragDropInput, GestureInput, GestureInputData, KeyInput, KeyInputData,
+    PointerId, PointerInput, PointerInputData, PointerInputPosition, ScrollInput,
+};
 
 macro_rules! impl_signal {
-    ($variant:ident, $name:ident, $marker:ident) => {
+    ($variant:ident, $name:ident, $marker:ident, $bubbles:literal) => {
         // #[derive(Event)]
         pub struct $name;
         impl Signal for $name {
             type Event = PointerInput;
             type Args = PointerInputPosition;
             type Descriptor = $marker;
+            const BUBBLES: bool = $bubbles;
             fn filter(event: &Self::Event) -> Option<Entity> {
                 matches!(event.data, PointerInputData::$variant).then_some(event.entity)
             }
@@ -21,6 +27,13 @@ macro_rules! impl_signal {
                 &$marker
             }
         }
+        impl $crate::SignalDescriptor for $marker {
+            type Signal = $name;
+            fn prepare(entity: &mut ::bevy::ecs::world::EntityWorldMut) {
+                entity.insert(::polako_input::PointerFilter::Pass);
+            }
+        }
+        impl $crate::HandModifiers for $marker {}
         impl $marker {
             pub fn emit(
                 &self,
@@ -32,6 +45,10 @@ macro_rules! impl_signal {
                     .get_resource_or_insert_with(Events::<PointerInput>::default)
                     .send(PointerInput {
                         entity,
+                        pointer: PointerId::Mouse,
+                        button: PointerInputData::$variant
+                            .is_button()
+                            .then_some(MouseButton::Left),
                         position,
                         data: PointerInputData::$variant,
                     })
@@ -48,32 +65,245 @@ macro_rules! impl_signal {
                 &self,
                 entity: &mut ::bevy::ecs::world::EntityWorldMut<'w>,
                 func: F,
-            ) {
-                let hand = $crate::Hand::new(func);
-                if !entity.contains::<$crate::Hands<<$name as $crate::Signal>::Event, S>>() {
-                    entity.insert(($crate::Hands(vec![hand]), $crate::FlowItem));
-                } else {
-                    entity
-                        .get_mut::<$crate::Hands<<$name as $crate::Signal>::Event, S>>()
-                        .unwrap()
-                        .push(hand);
-                }
+            ) -> $crate::HandId {
+                <$marker as $crate::SignalDescriptor>::prepare(entity);
+                entity.add_signal_hand::<$name, S>($crate::Hand::new(func))
+            }
+        }
+    };
+}
+
+impl_signal!(Up, UpSignal, UpSignalMarker, true);
+impl_signal!(Down, DownSignal, DownSignalMarker, true);
+impl_signal!(Motion, MotionSignal, MotionSignalMarker, true);
+impl_signal!(DragStart, DragStSignal, DragStartSignalMarker, true);
+impl_signal!(Drag, DragSignal, DragSignalMarker, true);
+impl_signal!(DragStop, DragStopSignal, DragStopSignalMarker, true);
+impl_signal!(Click, ClickSignal, ClickSignalMarker, true);
+impl_signal!(DoubleClick, DoubleClickSignal, DoubleClickSignalMarker, true);
+impl_signal!(LongPress, LongPressSignal, LongPressSignalMarker, true);
+impl_signal!(Enter, HoverSignal, HoverSignalMarker, false);
+impl_signal!(Leave, LeaveSignal, LeaveSignalMarker, false);
+impl_signal!(Over, OverSignal, OverSignalMarker, true);
+impl_signal!(Out, OutSignal, OutSignalMarker, true);
+impl_signal!(Focus, FocusSignal, FocusSignalMarker, false);
+impl_signal!(Blur, BlurSignal, BlurSignalMarker, false);
+impl_signal!(LostCapture, LostCaptureSignal, LostCaptureSignalMarker, false);
+
+macro_rules! impl_key_signal {
+    ($variant:ident, $name:ident, $marker:ident, $arg:ident: $arg_ty:ty) => {
+        pub struct $name;
+        impl Signal for $name {
+            type Event = KeyInput;
+            type Args = ();
+            type Descriptor = $marker;
+            const BUBBLES: bool = true;
+            fn filter(event: &Self::Event) -> Option<Entity> {
+                matches!(event.data, KeyInputData::$variant(_)).then_some(event.entity)
+            }
+        }
+        pub struct $marker;
+        impl Singleton for $marker {
+            fn instance() -> &'static $marker {
+                &$marker
+            }
+        }
+        impl $crate::SignalDescriptor for $marker {
+            type Signal = $name;
+        }
+        impl $crate::HandModifiers for $marker {}
+        impl $marker {
+            pub fn emit(&self, world: &mut World, entity: Entity, $arg: $arg_ty) {
+                world
+                    .get_resource_or_insert_with(Events::<KeyInput>::default)
+                    .send(KeyInput {
+                        entity,
+                        data: KeyInputData::$variant($arg),
+                    })
+            }
+
+            pub fn assign<
+                'w,
+                S: ::bevy::ecs::system::SystemParam + 'static,
+                F: Fn(
+                        &<$name as $crate::Signal>::Event,
+                        &mut ::bevy::ecs::system::StaticSystemParam<S>,
+                    ) + 'static,
+            >(
+                &self,
+                entity: &mut ::bevy::ecs::world::EntityWorldMut<'w>,
+                func: F,
+            ) -> $crate::HandId {
+                entity.add_signal_hand::<$name, S>($crate::Hand::new(func))
+            }
+        }
+    };
+}
+
+// key signals are sent to the focused element and bubble up to its ancestors
+impl_key_signal!(Down, KeyDownSignal, KeyDownSignalMarker, key: KeyCode);
+impl_key_signal!(Up, KeyUpSignal, KeyUpSignalMarker, key: KeyCode);
+impl_key_signal!(Char, CharSignal, CharSignalMarker, char: char);
+
+macro_rules! impl_gesture_signal {
+    ($variant:ident, $name:ident, $marker:ident) => {
+        pub struct $name;
+        impl Signal for $name {
+            type Event = GestureInput;
+            type Args = ();
+            type Descriptor = $marker;
+            const BUBBLES: bool = true;
+            fn filter(event: &Self::Event) -> Option<Entity> {
+                matches!(event.data, GestureInputData::$variant { .. }).then_some(event.entity)
+            }
+        }
+        pub struct $marker;
+        impl Singleton for $marker {
+            fn instance() -> &'static $marker {
+                &$marker
+            }
+        }
+        impl $crate::SignalDescriptor for $marker {
+            type Signal = $name;
+            fn prepare(entity: &mut ::bevy::ecs::world::EntityWorldMut) {
                 entity.insert(::polako_input::PointerFilter::Pass);
-                entity.world_scope(|world| {
-                    world
-                        .resource::<$crate::FlowResource>()
-                        .register_handle_signals_systems::<$name, S>();
-                });
+            }
+        }
+        impl $crate::HandModifiers for $marker {}
+        impl $marker {
+            pub fn emit(&self, world: &mut World, entity: Entity, data: GestureInputData) {
+                world
+                    .get_resource_or_insert_with(Events::<GestureInput>::default)
+                    .send(GestureInput { entity, data })
+            }
+
+            pub fn assign<
+                'w,
+                S: ::bevy::ecs::system::SystemParam + 'static,
+                F: Fn(
+                        &<$name as $crate::Signal>::Event,
+                        &mut ::bevy::ecs::system::StaticSystemParam<S>,
+                    ) + 'static,
+            >(
+                &self,
+                entity: &mut ::bevy::ecs::world::EntityWorldMut<'w>,
+                func: F,
+            ) -> $crate::HandId {
+                <$marker as $crate::SignalDescriptor>::prepare(entity);
+                entity.add_signal_hand::<$name, S>($crate::Hand::new(func))
+            }
+        }
+    };
+}
+
+// gestures are sent to the element the first touch was pressed on and bubble
+impl_gesture_signal!(Pinch, PinchSignal, PinchSignalMarker);
+impl_gesture_signal!(Rotate, RotateSignal, RotateSignalMarker);
+impl_gesture_signal!(Pan, PanSignal, PanSignalMarker);
+impl_gesture_signal!(Swipe, SwipeSignal, SwipeSignalMarker);
+
+/// The wheel over the element, bubbles up to the scrollable ancestors.
+pub struct ScrollSignal;
+impl Signal for ScrollSignal {
+    type Event = ScrollInput;
+    type Args = ();
+    type Descriptor = ScrollSignalMarker;
+    const BUBBLES: bool = true;
+    fn filter(event: &Self::Event) -> Option<Entity> {
+        Some(event.entity)
+    }
+}
+pub struct ScrollSignalMarker;
+impl Singleton for ScrollSignalMarker {
+    fn instance() -> &'static ScrollSignalMarker {
+        &ScrollSignalMarker
+    }
+}
+impl crate::SignalDescriptor for ScrollSignalMarker {
+    type Signal = ScrollSignal;
+    fn prepare(entity: &mut ::bevy::ecs::world::EntityWorldMut) {
+        entity.insert(::polako_input::PointerFilter::Pass);
+    }
+}
+impl crate::HandModifiers for ScrollSignalMarker {}
+impl ScrollSignalMarker {
+    pub fn emit(&self, world: &mut World, entity: Entity, lines: Vec2) {
+        world
+            .get_resource_or_insert_with(Events::<ScrollInput>::default)
+            .send(ScrollInput {
+                entity,
+                pointer: PointerId::Mouse,
+                position: PointerInputPosition::default(),
+                lines,
+                pixels: Vec2::ZERO,
+                scrollable: None,
+            })
+    }
+
+    pub fn assign<
+        'w,
+        S: ::bevy::ecs::system::SystemParam + 'static,
+        F: Fn(&ScrollInput, &mut ::bevy::ecs::system::StaticSystemParam<S>) + 'static,
+    >(
+        &self,
+        entity: &mut ::bevy::ecs::world::EntityWorldMut<'w>,
+        func: F,
+    ) -> crate::HandId {
+        <ScrollSignalMarker as crate::SignalDescriptor>::prepare(entity);
+        entity.add_signal_hand::<ScrollSignal, S>(crate::Hand::new(func))
+    }
+}
+
+macro_rules! impl_drop_signal {
+    ($variant:ident, $name:ident, $marker:ident) => {
+        pub struct $name<P>(PhantomData<P>);
+        impl<P: Component + Clone> Signal for $name<P> {
+            type Event = DragDropInput<P>;
+            type Args = ();
+            type Descriptor = $marker<P>;
+            fn filter(event: &Self::Event) -> Option<Entity> {
+                matches!(event.data, DragDropData::$variant).then_some(event.entity)
+            }
+        }
+        pub struct $marker<P>(PhantomData<P>);
+        impl<P: Component + Clone> Singleton for $marker<P> {
+            fn instance() -> &'static $marker<P> {
+                &$marker(PhantomData)
+            }
+        }
+        impl<P: Component + Clone> $crate::SignalDescriptor for $marker<P> {
+            type Signal = $name<P>;
+            fn prepare(entity: &mut ::bevy::ecs::world::EntityWorldMut) {
+                entity.insert(::polako_input::PointerFilter::Pass);
+                if !entity.contains::<::polako_input::DropTarget<P>>() {
+                    entity.insert(::polako_input::DropTarget::<P>::new());
+                }
+            }
+        }
+        impl<P: Component + Clone> $crate::HandModifiers for $marker<P> {}
+        impl<P: Component + Clone> $marker<P> {
+            pub fn assign<
+                'w,
+                S: ::bevy::ecs::system::SystemParam + 'static,
+                F: Fn(
+                        &<$name<P> as $crate::Signal>::Event,
+                        &mut ::bevy::ecs::system::StaticSystemParam<S>,
+                    ) + 'static,
+            >(
+                &self,
+                entity: &mut ::bevy::ecs::world::EntityWorldMut<'w>,
+                func: F,
+            ) -> $crate::HandId {
+                <$marker<P> as $crate::SignalDescriptor>::prepare(entity);
+                entity.add_signal_hand::<$name<P>, S>($crate::Hand::new(func))
             }
         }
     };
 }
 
-impl_signal!(Up, UpSignal, UpSignalMarker);
-impl_signal!(Down, DownSignal, DownSignalMarker);
-impl_signal!(Motion, MotionSignal, MotionSignalMarker);
-impl_signal!(DragStart, DragStSignal, DragStartSignalMarker);
-impl_signal!(Drag, DragSignal, DragSignalMarker);
-impl_signal!(DragStop, DragStopSignal, DragStopSignalMarker);
-impl_signal!(Hover, HoverSignal, HoverSignalMarker);
-impl_signal!(Focus, FocusSignal, FocusSignalMarker);
+// sent to the `DropTarget<P>`, the drop target is inserted with the first hand,
+// `DragDropPlugin::<P>` should be added to the app
+impl_drop_signal!(Enter, DragEnterSignal, DragEnterSignalMarker);
+impl_drop_signal!(Over, DragOverSignal, DragOverSignalMarker);
+impl_drop_signal!(Leave, DragLeaveSignal, DragLeaveSignalMarker);
+impl_drop_signal!(Drop, DropSignal, DropSignalMarker);
@@ -1,34 +1,137 @@
 use std::{
-    any::TypeId, cell::RefCell, marker::PhantomData, rc::Rc, sync::RwLock, thread::ThreadId,
+    any::TypeId,
+    cell::{Cell, RefCell},
+    collections::BTreeMap,
+    marker::PhantomData,
+    rc::Rc,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        RwLock,
+    },
+    thread::ThreadId,
 };
 
 use bevy::{
     ecs::{
+        event::event_update_system,
         system::{Command, StaticSystemParam, SystemBuffer, SystemParam},
         world::EntityWorldMut, schedule::ScheduleLabel,
     },
+    hierarchy::HierarchyQueryExt,
     prelude::*,
-    utils::{HashMap, HashSet},
+    utils::{tracing::trace_span, HashMap, HashSet},
 };
 use polako_channel::Channel;
 use polako_constructivism::*;
 
+mod change;
+mod events;
+mod exit;
+pub mod graph;
 pub mod input;
+mod phase;
+mod propagation;
+mod stats;
+
+pub use change::*;
+pub use events::*;
+pub use exit::*;
+pub use graph::*;
+pub use phase::*;
+pub use propagation::*;
+pub use stats::*;
+
+#[cfg(test)]
+mod tests;
+
+/// Runs the flow loop in `Update` by default:
+/// ```ignore
+/// app.add_plugins(FlowPlugin::default());
+/// app.add_plugins(FlowPlugin::default().in_schedule(PostUpdate).after(PhysicsSet));
+/// ```
+/// See [`FlowPhase`] for the several independent loops.
+pub struct FlowPlugin {
+    /// `None` is the main loop in `Update`.
+    main: Option<FlowPhase>,
+    phases: Vec<FlowPhase>,
+}
+
+impl Default for FlowPlugin {
+    /// The flow loop in `Update`.
+    fn default() -> Self {
+        FlowPlugin {
+            main: None,
+            phases: vec![],
+        }
+    }
+}
+
+impl FlowPlugin {
+    fn main(&mut self) -> FlowPhase {
+        self.main
+            .take()
+            .unwrap_or_else(|| FlowPhase::new(FlowPhase::MAIN, Update))
+    }
+
+    /// Moves the main flow loop to the `schedule`.
+    pub fn in_schedule(mut self, schedule: impl ScheduleLabel + Clone) -> Self {
+        self.main = Some(self.main().in_schedule(schedule));
+        self
+    }
+
+    /// Runs the main flow loop after the `set`.
+    pub fn after<M>(mut self, set: impl IntoSystemSet<M> + Clone + Send + Sync + 'static) -> Self {
+        self.main = Some(self.main().after(set));
+        self
+    }
+
+    /// Runs the main flow loop before the `set`.
+    pub fn before<M>(mut self, set: impl IntoSystemSet<M> + Clone + Send + Sync + 'static) -> Self {
+        self.main = Some(self.main().before(set));
+        self
+    }
+
+    /// Adds the independent flow loop, see [`WorldFlow::with_flow_phase`].
+    pub fn with_phase(mut self, phase: FlowPhase) -> Self {
+        if phase.name() == FlowPhase::MAIN {
+            panic!("Flow phase `{}` is reserved", FlowPhase::MAIN);
+        }
+        self.phases.push(phase);
+        self
+    }
+}
 
-pub struct FlowPlugin;
 impl Plugin for FlowPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, flow_loop);
-        app.init_resource::<FlowIteration>();
+        app.insert_resource(FlowPhases::new());
         app.insert_resource(FlowResource::new());
+        match &self.main {
+            Some(main) => main.build(app),
+            None => FlowPhase::new(FlowPhase::MAIN, Update).build(app),
+        }
+        for phase in self.phases.iter() {
+            phase.build(app);
+        }
+        app.init_resource::<FlowIteration>();
         app.insert_resource(BindTargets::new());
         app.insert_resource(BypassUpdates::new());
+        app.init_resource::<HandRegistry>();
+        app.init_resource::<FlowHold>();
+        app.init_resource::<FlowStats>();
+        app.add_systems(First, rotate_flow_stats);
         app.add_event::<EnterSignal>();
         app.add_event::<UpdateSignal>();
+        app.add_event::<ExitSignal>();
+        app.init_resource::<SignalDispatch>();
+        app.init_resource::<SignalDispatchLock>();
     }
 }
 
 pub fn flow_loop(world: &mut World) {
+    if world.resource::<FlowHold>().0 > 0 {
+        // changes are kept by the change detection until the hold is released
+        return;
+    }
     let flow = world.resource::<FlowResource>().clone();
 
     // start the loop
@@ -45,6 +148,7 @@ pub fn flow_loop(world: &mut World) {
         flow.commands.take().into_iter().for_each(|c| c(world));
 
         // process schedule
+        world.resource::<FlowStats>().iteration();
         schedule_ref.run(world);
 
         // any changes?
@@ -68,8 +172,14 @@ pub enum FlowSet {
     Write,
     HandleSignals,
     PopulateChanges,
+    Exit,
 }
 
+/// The signal systems are executed in the order they were registered,
+/// `SignalOrder(n)` goes after `SignalOrder(n - 1)`.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
+struct SignalOrder(usize);
+
 fn first_iteration(iteration: Res<FlowIteration>) -> bool {
     iteration.first()
 }
@@ -82,7 +192,13 @@ fn collect_changes<T: Component>(
     changed: Query<Entity, Changed<T>>,
     mut changes: ResMut<ChangedEntities<T>>,
 ) {
-    changes.entities.extend(changed.iter());
+    for entity in changed.iter() {
+        // changed outside of the flow, every prop could be changed
+        changes.props.remove(&entity);
+        changes.entities.push(entity);
+    }
+    changes.entities.sort_unstable();
+    changes.entities.dedup();
 }
 
 fn cleanup_component_readers<S: Component, T: Component, V: Bindable>(
@@ -93,7 +209,7 @@ fn cleanup_component_readers<S: Component, T: Component, V: Bindable>(
     for target in removals.read() {
         for source in targets.0.remove(&target).unwrap_or_default().iter() {
             if let Ok(mut source) = sources.get_mut(*source) {
-                source.0.remove(&target);
+                source.0.retain(|_, s| s.target != target);
             }
         }
     }
@@ -108,21 +224,60 @@ fn cleanup_on_demand_updates(
     }
 }
 
+fn cleanup_hand_registry(
+    mut removals: RemovedComponents<FlowItem>,
+    mut registry: ResMut<HandRegistry>,
+) {
+    let removed = removals.read().collect::<HashSet<_>>();
+    if !removed.is_empty() {
+        registry.0.retain(|_, (entity, _)| !removed.contains(entity));
+    }
+}
+
 fn read_component_changes<S: Component, T: Component, V: Bindable>(
-    components: Query<(&ComponentBindSources<S, T, V>, &S)>,
+    components: Query<(Entity, &ComponentBindSources<S, T, V>, &S)>,
     changed: Res<ChangedEntities<S>>,
     changes: Changes<T, V>,
+    phases: Res<FlowPhases>,
+    stats: Res<FlowStats>,
 ) {
-    for (sources, component) in components.iter_many(changed.entities.iter()) {
-        for items in sources.0.values() {
-            for source in items.iter() {
-                let value = source.read.read(&component).get();
-                changes.send(ApplyChange {
-                    value,
-                    target: source.target,
-                    writer: source.writer.clone(),
-                })
+    let phase = phases.active();
+    let _span = trace_span!(
+        "binds",
+        source = std::any::type_name::<S>(),
+        target = std::any::type_name::<T>()
+    )
+    .entered();
+    for (entity, sources, component) in components.iter_many(changed.iter()) {
+        let props = changed.props.get(&entity);
+        for source in sources.0.values().filter(|s| s.phase == phase) {
+            // only the isolated readers can skip the changes of the other props
+            if source.isolated
+                && props.map_or(false, |p| !p.iter().any(|p| props_overlap(p, source.from_prop)))
+            {
+                continue;
             }
+            let _span = trace_span!(
+                "bind",
+                entity = ?entity,
+                from = source.from_prop,
+                target = ?source.target,
+                to = source.to_prop
+            )
+            .entered();
+            stats.bind_evaluated();
+            let Some(value) = source.read.read(&component) else {
+                continue;
+            };
+            let value = value.get();
+            changes.send(ApplyChange {
+                value,
+                target: source.target,
+                writer: source.writer.clone(),
+                compare: source.compare,
+                order: source.order,
+                prop: source.to_prop,
+            })
         }
     }
 }
@@ -131,8 +286,9 @@ fn cleanup_resource_readers<S: Resource, T: Component, V: Bindable>(
     mut sources: ResMut<ResourceBindSources<S, T, V>>,
     mut removals: RemovedComponents<FlowItem>,
 ) {
-    for target in removals.read() {
-        sources.0.remove(&target);
+    let removed = removals.read().collect::<HashSet<_>>();
+    if !removed.is_empty() {
+        sources.0.retain(|_, s| !removed.contains(&s.target));
     }
 }
 
@@ -140,17 +296,38 @@ fn read_resource_changes<S: Resource, T: Component, V: Bindable>(
     res: Res<S>,
     sources: Res<ResourceBindSources<S, T, V>>,
     changes: Changes<T, V>,
+    phases: Res<FlowPhases>,
+    stats: Res<FlowStats>,
 ) {
     if res.is_changed() {
-        for sources in sources.0.values() {
-            for source in sources.iter() {
-                let value = source.read.read(&res).get();
-                changes.send(ApplyChange {
-                    value,
-                    target: source.target,
-                    writer: source.writer.clone(),
-                })
-            }
+        let phase = phases.active();
+        let _span = trace_span!(
+            "binds",
+            source = std::any::type_name::<S>(),
+            target = std::any::type_name::<T>()
+        )
+        .entered();
+        for source in sources.0.values().filter(|s| s.phase == phase) {
+            let _span = trace_span!(
+                "bind",
+                from = source.from_prop,
+                target = ?source.target,
+                to = source.to_prop
+            )
+            .entered();
+            stats.bind_evaluated();
+            let Some(value) = source.read.read(&res) else {
+                continue;
+            };
+            let value = value.get();
+            changes.send(ApplyChange {
+                value,
+                target: source.target,
+                writer: source.writer.clone(),
+                compare: source.compare,
+                order: source.order,
+                prop: source.to_prop,
+            })
         }
     }
 }
@@ -161,35 +338,57 @@ fn cleanup_write_component_changes<T: Component>(changed_entities: Res<Channel<C
 
 fn write_component_changes<T: Component, V: Bindable>(
     mut components: Query<(Entity, &mut T)>,
-    changes: Changes<T, V>,
+    mut changes: ResMut<Channel<ApplyChange<T, V>>>,
     changed_entities: Res<Channel<ChangedEntity<T>>>,
+    mut written: Local<HashSet<(Entity, WriteKey)>>,
+    stats: Res<FlowStats>,
 ) {
-    changes.recv(|change| {
+    // the channel yields the changes in arbitrary order (the changes are
+    // bucketed per thread), so they are applied in the bind order: the bind
+    // with the highest priority, the latest registered one between the equal
+    // priorities goes last. Only the last change per (target, prop) is applied.
+    let mut pending = vec![];
+    changes.consume(|change| pending.push(change));
+    pending.sort_by_key(|change| change.order);
+    written.clear();
+    pending.reverse();
+    pending.retain(|change| written.insert(change.key()));
+    pending.reverse();
+    for change in pending {
         let Ok((entity, mut component)) = components.get_mut(change.target) else {
-            return;
+            continue;
         };
-        if change.writer.read(component.as_ref()).as_ref() == &change.value {
-            return;
+        match change.writer.read(component.as_ref()) {
+            Some(current) if change.compare.same(current.as_ref(), &change.value) => continue,
+            // the indexed target is out of range
+            None if !change.writer.is_write_only() => continue,
+            _ => {}
         }
-        change
-            .writer
-            .write(component.as_mut(), change.value.clone());
-        changed_entities.send(ChangedEntity::new(entity));
-    });
+        if change.writer.write(component.as_mut(), change.value) {
+            stats.write_applied();
+            changed_entities.send(ChangedEntity::new(entity).with_prop(change.prop));
+        }
+    }
 }
 
 fn populate_changes<T: Component>(
     changes: Res<Channel<ChangedEntity<T>>>,
     mut changed_entities: ResMut<ChangedEntities<T>>,
     mut flow: Deferred<FlowLoopControl>,
+    mut pairs: Local<Vec<(Entity, &'static str)>>,
 ) {
-    changed_entities.entities.clear();
+    pairs.clear();
     changes.recv(|change| {
         flow.repeat();
-        changed_entities.entities.insert(change.entity);
-    })
+        pairs.push((change.entity, change.prop));
+    });
+    changed_entities.set(&mut pairs);
 }
 
+/// The number of running [`WorldFlow::flow_batch`] calls.
+#[derive(Resource, Default)]
+struct FlowHold(usize);
+
 #[derive(Resource, Clone, Copy, Default)]
 enum FlowIteration {
     #[default]
@@ -243,8 +442,23 @@ struct FlowResource(Rc<Flow>);
 unsafe impl Send for FlowResource {}
 unsafe impl Sync for FlowResource {}
 impl FlowResource {
+    /// The flow of the main phase, it maintains the state shared by the phases.
     fn new() -> Self {
-        FlowResource(Rc::new(Flow::new()))
+        let flow = Flow::new(Rc::new(RegisteredSystems::new(default())));
+        flow.schedule.borrow_mut().add_systems((
+            cleanup_on_demand_updates.in_set(FlowSet::CleanupReaders),
+            cleanup_hand_registry.in_set(FlowSet::CleanupReaders),
+            exit_removed_children.in_set(FlowSet::CleanupReaders),
+            despawn_exited.in_set(FlowSet::Exit),
+        ));
+        FlowResource(Rc::new(flow))
+    }
+
+    /// The flow of the additional phase with its own registry, only the
+    /// graph inspectors are shared with `self`.
+    pub(crate) fn phase(&self) -> Self {
+        let registry = RegisteredSystems::new(self.registry.inspectors.clone());
+        FlowResource(Rc::new(Flow::new(Rc::new(registry))))
     }
 }
 
@@ -252,7 +466,7 @@ struct Flow {
     schedule: RefCell<Schedule>,
     queue: RefCell<Vec<Box<dyn FnOnce(&mut Schedule)>>>,
     commands: RefCell<Vec<Box<dyn FnOnce(&mut World)>>>,
-    registry: RegisteredSystems,
+    registry: Rc<RegisteredSystems>,
 }
 
 struct HashCell(RefCell<HashSet<TypeId>>);
@@ -263,6 +477,10 @@ impl HashCell {
             func()
         }
     }
+
+    fn len(&self) -> usize {
+        self.0.borrow().len()
+    }
 }
 struct RegisteredSystems {
     cleanup_changes: HashCell,
@@ -273,10 +491,16 @@ struct RegisteredSystems {
     handle_enters: HashCell,
     handle_updates: HashCell,
     handle_signals: HashCell,
+    watch_component: HashCell,
+    watch_resource: HashCell,
+    read_events: HashCell,
+    change_events: HashCell,
+    /// Shared by the phases, so the graph shows the binds of every phase.
+    inspectors: Rc<RefCell<Vec<fn(&World, &mut FlowGraph)>>>,
 }
 
 impl RegisteredSystems {
-    fn new() -> Self {
+    fn new(inspectors: Rc<RefCell<Vec<fn(&World, &mut FlowGraph)>>>) -> Self {
         RegisteredSystems {
             cleanup_changes: HashCell(RefCell::new(HashSet::new())),
             read_component: HashCell(RefCell::new(HashSet::new())),
@@ -286,6 +510,11 @@ impl RegisteredSystems {
             handle_enters: HashCell(RefCell::new(HashSet::new())),
             handle_updates: HashCell(RefCell::new(HashSet::new())),
             handle_signals: HashCell(RefCell::new(HashSet::new())),
+            watch_component: HashCell(RefCell::new(HashSet::new())),
+            watch_resource: HashCell(RefCell::new(HashSet::new())),
+            read_events: HashCell(RefCell::new(HashSet::new())),
+            change_events: HashCell(RefCell::new(HashSet::new())),
+            inspectors,
         }
     }
 }
@@ -294,7 +523,7 @@ impl RegisteredSystems {
 pub struct FlowLabel;
 
 impl Flow {
-    fn new() -> Self {
+    fn new(registry: Rc<RegisteredSystems>) -> Self {
         let mut schedule = Schedule::new(FlowLabel);
         schedule.configure_sets((
             FlowSet::CleanupReaders.after(FlowSet::CleanupChanges),
@@ -304,14 +533,14 @@ impl Flow {
             FlowSet::Write.after(FlowSet::CleanupWriteChanges),
             FlowSet::HandleSignals.after(FlowSet::Write),
             FlowSet::PopulateChanges.after(FlowSet::HandleSignals),
+            FlowSet::Exit.after(FlowSet::PopulateChanges),
         ));
-        schedule.add_systems(cleanup_on_demand_updates.in_set(FlowSet::CleanupReaders));
-        // schedule.
+        add_stats_systems(&mut schedule);
         Self {
             schedule: RefCell::new(schedule),
             queue: RefCell::new(vec![]),
             commands: RefCell::new(vec![]),
-            registry: RegisteredSystems::new(),
+            registry,
         }
     }
 
@@ -321,6 +550,13 @@ impl Flow {
     fn edit_world<F: FnOnce(&mut World) + 'static>(&self, func: F) {
         self.commands.borrow_mut().push(Box::new(func))
     }
+    fn inspect(&self, inspector: fn(&World, &mut FlowGraph)) {
+        let mut inspectors = self.registry.inspectors.borrow_mut();
+        // the same systems could be registered by several phases
+        if !inspectors.iter().any(|i| *i as usize == inspector as usize) {
+            inspectors.push(inspector)
+        }
+    }
 
     fn register_populate_systems<C: Component>(&self) {
         self.registry.populate_changes.register::<C, _>(|| {
@@ -336,8 +572,11 @@ impl Flow {
                 );
             });
             self.edit_world(|world| {
-                world.insert_resource(Channel::<ChangedEntity<C>>::new());
-                world.insert_resource(ChangedEntities::<C>::new());
+                // could be already inserted by the other phase
+                if !world.contains_resource::<ChangedEntities<C>>() {
+                    world.insert_resource(Channel::<ChangedEntity<C>>::new());
+                    world.insert_resource(ChangedEntities::<C>::new());
+                }
             });
         });
     }
@@ -356,6 +595,7 @@ impl Flow {
                 );
                 schedule.add_systems(read_component_changes::<S, T, V>.in_set(FlowSet::Read));
             });
+            self.inspect(graph::inspect_component_binds::<S, T, V>);
         })
     }
 
@@ -372,6 +612,27 @@ impl Flow {
                 );
                 schedule.add_systems(read_resource_changes::<S, T, V>.in_set(FlowSet::Read));
             });
+            self.inspect(graph::inspect_resource_binds::<S, T, V>);
+        });
+    }
+
+    fn register_event_read_systems<E: Event, T: Component, V: Bindable>(&self) {
+        self.registry.cleanup_changes.register::<(T, V), _>(|| {
+            self.edit_schedule(|schedule| {
+                schedule.add_systems(cleanup_changes::<T, V>.in_set(FlowSet::CleanupChanges));
+            });
+        });
+        self.registry.read_events.register::<(E, T, V), _>(|| {
+            self.edit_world(|world| {
+                world.init_resource::<Events<E>>();
+            });
+            self.edit_schedule(|schedule| {
+                schedule.add_systems(
+                    cleanup_event_readers::<E, T, V>.in_set(FlowSet::CleanupReaders),
+                );
+                schedule.add_systems(read_event_changes::<E, T, V>.in_set(FlowSet::Read));
+            });
+            self.inspect(inspect_event_binds::<E, T, V>);
         });
     }
 
@@ -382,7 +643,51 @@ impl Flow {
                 schedule.add_systems(write_component_changes::<T, V>.in_set(FlowSet::Write));
             });
             self.edit_world(|world| {
-                world.insert_resource(Channel::<ApplyChange<T, V>>::new());
+                if !world.contains_resource::<Channel<ApplyChange<T, V>>>() {
+                    world.insert_resource(Channel::<ApplyChange<T, V>>::new());
+                }
+            });
+        });
+    }
+
+    fn register_component_watch_systems<C: Component, V: Bindable>(&self) {
+        self.register_populate_systems::<C>();
+        self.register_change_events::<V>();
+        self.registry.watch_component.register::<(C, V), _>(|| {
+            self.edit_schedule(|schedule| {
+                schedule.add_systems(
+                    cleanup_component_watchers::<C, V>.in_set(FlowSet::CleanupReaders),
+                );
+                schedule.add_systems(watch_component_changes::<C, V>.in_set(FlowSet::Read));
+            });
+        });
+    }
+
+    fn register_resource_watch_systems<R: Resource, V: Bindable>(&self) {
+        self.register_change_events::<V>();
+        self.registry.watch_resource.register::<(R, V), _>(|| {
+            self.edit_schedule(|schedule| {
+                schedule.add_systems(
+                    cleanup_resource_watchers::<R, V>.in_set(FlowSet::CleanupReaders),
+                );
+                schedule.add_systems(watch_resource_changes::<R, V>.in_set(FlowSet::Read));
+            });
+        });
+    }
+
+    /// The `ChangeSignal`s are not registered with `add_event`, the flow drops
+    /// the old ones at the start of the frame.
+    fn register_change_events<V: Bindable>(&self) {
+        self.registry.change_events.register::<V, _>(|| {
+            self.edit_world(|world| {
+                world.init_resource::<Events<ChangeSignal<V>>>();
+            });
+            self.edit_schedule(|schedule| {
+                schedule.add_systems(
+                    event_update_system::<ChangeSignal<V>>
+                        .in_set(FlowSet::CleanupChanges)
+                        .run_if(first_iteration),
+                );
             });
         });
     }
@@ -391,7 +696,8 @@ impl Flow {
         self.registry.handle_enters.register::<S, _>(|| {
             self.edit_schedule(|schedule| {
                 schedule.add_systems(handle_enters::<S>.in_set(FlowSet::HandleSignals));
-            })
+            });
+            self.inspect(graph::inspect_hands::<EnterSignal, S>);
         })
     }
     fn register_handle_update_systems<S: SystemParam + 'static>(&self) {
@@ -404,6 +710,7 @@ impl Flow {
                         .run_if(first_iteration),
                 );
             });
+            self.inspect(graph::inspect_hands::<UpdateSignal, S>);
         })
     }
     fn register_handle_signals_systems<E: Signal, S: SystemParam + 'static>(&self) {
@@ -411,57 +718,142 @@ impl Flow {
             self.edit_world(|world| {
                 world.init_resource::<Events<<E as Signal>::Event>>();
             });
+            let order = self.registry.handle_signals.len();
             self.edit_schedule(|schedule| {
+                if order > 1 {
+                    schedule.configure_sets(SignalOrder(order).after(SignalOrder(order - 1)));
+                }
                 schedule.add_systems(
                     handle_signals_system::<E, S>
                         .in_set(FlowSet::HandleSignals)
+                        .in_set(SignalOrder(order))
                         .after(handle_updates::<S>),
                 );
             });
+            self.inspect(graph::inspect_hands::<<E as Signal>::Event, S>);
         });
     }
 }
 
+/// Ordering guarantees, the same registrations always produce the same state:
+/// - when several binds write the same target prop in one iteration, the bind
+///   with the highest [`ComponentWriter::priority`] wins, then the latest
+///   registered one;
+/// - hands of the entity are executed by [`HandOptions::priority`], then in
+///   the registration order;
+/// - the changes are applied in the bind order, no matter which thread the
+///   reading system was executed on;
+/// - signals of one type are dispatched in the order they were emitted, the
+///   signal systems are executed in the order they were registered. The
+///   reading and watching systems may run in parallel and in any order.
 pub trait WorldFlow {
-    fn bind_component_to_component<S: Component, T: Component, V: Bindable>(
+    fn bind_component_to_component<S: Component, T: Component, V: Bindable + PartialEq>(
+        &mut self,
+        from: ComponentReader<S, V>,
+        to: ComponentWriter<T, V>,
+    ) {
+        self.bind_component_to_component_with(from, to, Comparator::eq())
+    }
+
+    fn bind_resource_to_component<R: Resource, T: Component, V: Bindable + PartialEq>(
+        &mut self,
+        from: ResourceReader<R, V>,
+        to: ComponentWriter<T, V>,
+    ) {
+        self.bind_resource_to_component_with(from, to, Comparator::eq())
+    }
+
+    /// Binds using `compare` to decide if the target should be written.
+    fn bind_component_to_component_with<S: Component, T: Component, V: Bindable>(
         &mut self,
         from: ComponentReader<S, V>,
         to: ComponentWriter<T, V>,
+        compare: Comparator<V>,
+    );
+
+    /// Binds using `compare` to decide if the target should be written.
+    fn bind_resource_to_component_with<R: Resource, T: Component, V: Bindable>(
+        &mut self,
+        from: ResourceReader<R, V>,
+        to: ComponentWriter<T, V>,
+        compare: Comparator<V>,
     );
 
-    fn bind_resource_to_component<R: Resource, T: Component, V: Bindable>(
+    /// Binds the latest or folded value of the `Events<E>`, see [`EventsReader`].
+    fn bind_events_to_component<E: Event, T: Component, V: Bindable + PartialEq>(
         &mut self,
-        from: Reader<R, V>,
+        from: EventsReader<E, V>,
         to: ComponentWriter<T, V>,
+    ) {
+        self.bind_events_to_component_with(from, to, Comparator::eq())
+    }
+
+    /// Binds using `compare` to decide if the target should be written.
+    fn bind_events_to_component_with<E: Event, T: Component, V: Bindable>(
+        &mut self,
+        from: EventsReader<E, V>,
+        to: ComponentWriter<T, V>,
+        compare: Comparator<V>,
     );
+
+    /// Removes the hand from the entity it was assigned to. Returns false
+    /// if there is no such hand (already removed or the entity despawned).
+    fn remove_hand(&mut self, id: HandId) -> bool;
+
+    /// Emits the [`ExitSignal`] for the entity and its descendants and despawns
+    /// the entity recursively after the exit hands, unless some hand deferred the exit.
+    fn exit(&mut self, entity: Entity);
+
+    /// Releases the exit deferred with [`ExitSignal::defer`].
+    fn complete_exit(&mut self, entity: Entity);
+
+    /// Holds the flow loop while `func` applies the group of changes, then
+    /// propagates them at once:
+    /// ```ignore
+    /// world.flow_batch(|world| {
+    ///     world.get_mut::<Label>(a).unwrap().text = "a".into();
+    ///     world.get_mut::<Label>(b).unwrap().text = "b".into();
+    /// });
+    /// ```
+    /// Nested batches propagate when the outermost one ends.
+    fn flow_batch<R>(&mut self, func: impl FnOnce(&mut World) -> R) -> R;
+
+    /// Registers the binds and hands created by `func` in the flow `phase`
+    /// instead of the main one, see [`FlowPhase`].
+    fn with_flow_phase<R>(&mut self, phase: &'static str, func: impl FnOnce(&mut World) -> R) -> R;
 }
 
 impl WorldFlow for World {
-    fn bind_component_to_component<S: Component, T: Component, V: Bindable>(
+    fn bind_component_to_component_with<S: Component, T: Component, V: Bindable>(
         &mut self,
         from: ComponentReader<S, V>,
         to: ComponentWriter<T, V>,
+        compare: Comparator<V>,
     ) {
         // setup source
 
         // this component will be added to `from.entity`, all required generic systems will be
         // added to the `Flow` if needed
         let bind_source = BindSource {
+            phase: self.resource::<FlowPhases>().active(),
             target: to.entity,
             read: from.reader,
             writer: to.writer,
+            compare,
+            from_prop: from.prop,
+            isolated: from.isolated,
+            to_prop: to.prop,
+            order: BindOrder::next(to.priority),
         };
         {
             let mut e = self.entity_mut(from.entity);
             if !e.contains::<ComponentBindSources<S, T, V>>() {
-                e.insert(ComponentBindSources::<S, T, V>(HashMap::new()));
+                e.insert(ComponentBindSources::<S, T, V>(BTreeMap::new()));
             }
             e.get_mut::<ComponentBindSources<S, T, V>>()
                 .unwrap()
                 .0
-                .entry(to.entity)
-                .or_default()
-                .push(bind_source);
+                .insert(bind_source.order, bind_source);
         }
 
         // setup target
@@ -477,28 +869,91 @@ impl WorldFlow for World {
         flow.register_component_write_systems::<T, V>();
     }
 
-    fn bind_resource_to_component<S: Resource, T: Component, V: Bindable>(
+    fn bind_resource_to_component_with<S: Resource, T: Component, V: Bindable>(
         &mut self,
-        from: Reader<S, V>,
+        from: ResourceReader<S, V>,
         to: ComponentWriter<T, V>,
+        compare: Comparator<V>,
     ) {
         let bind_source = BindSource {
+            phase: self.resource::<FlowPhases>().active(),
             target: to.entity,
-            read: from,
+            read: from.reader,
             writer: to.writer,
+            compare,
+            from_prop: from.prop,
+            isolated: false,
+            to_prop: to.prop,
+            order: BindOrder::next(to.priority),
         };
 
         self.entity_mut(to.entity).insert(FlowItem);
         self.get_resource_or_insert_with(ResourceBindSources::<S, T, V>::new)
             .0
-            .entry(to.entity)
-            .or_default()
-            .push(bind_source);
+            .insert(bind_source.order, bind_source);
 
         let flow = self.resource::<FlowResource>().clone();
         flow.register_resource_read_systems::<S, T, V>();
         flow.register_component_write_systems::<T, V>();
     }
+
+    fn bind_events_to_component_with<E: Event, T: Component, V: Bindable>(
+        &mut self,
+        from: EventsReader<E, V>,
+        to: ComponentWriter<T, V>,
+        compare: Comparator<V>,
+    ) {
+        add_event_bind(self, from, to, compare);
+        let flow = self.resource::<FlowResource>().clone();
+        flow.register_event_read_systems::<E, T, V>();
+        flow.register_component_write_systems::<T, V>();
+    }
+
+    fn remove_hand(&mut self, id: HandId) -> bool {
+        let Some((entity, detach)) = self.resource_mut::<HandRegistry>().0.remove(&id) else {
+            return false;
+        };
+        detach(self, entity, id)
+    }
+
+    fn exit(&mut self, entity: Entity) {
+        Exit(entity).apply(self);
+    }
+
+    fn complete_exit(&mut self, entity: Entity) {
+        CompleteExit(entity).apply(self);
+    }
+
+    fn flow_batch<R>(&mut self, func: impl FnOnce(&mut World) -> R) -> R {
+        self.resource_mut::<FlowHold>().0 += 1;
+        let result = func(self);
+        let held = {
+            let mut hold = self.resource_mut::<FlowHold>();
+            hold.0 -= 1;
+            hold.0 > 0
+        };
+        // the batch inside the running loop propagates with the next iteration
+        if !held && self.resource::<FlowResource>().schedule.try_borrow_mut().is_ok() {
+            flow_loop(self);
+        }
+        result
+    }
+
+    fn with_flow_phase<R>(&mut self, phase: &'static str, func: impl FnOnce(&mut World) -> R) -> R {
+        let previous = enter_flow_phase(self, phase);
+        let result = func(self);
+        enter_flow_phase(self, previous);
+        result
+    }
+}
+
+/// Removes the hand by its id, see [`WorldFlow::remove_hand`].
+pub struct RemoveHand(pub HandId);
+
+impl Command for RemoveHand {
+    fn apply(self, world: &mut World) {
+        world.remove_hand(self.0);
+    }
 }
 
 pub trait EntityFlow {
@@ -509,7 +964,68 @@ pub trait EntityFlow {
     >(
         &mut self,
         handler: F,
-    );
+    ) -> HandId;
+
+    fn add_signal_hand<E: Signal, S: SystemParam + 'static>(
+        &mut self,
+        hand: Hand<<E as Signal>::Event, S>,
+    ) -> HandId;
+
+    /// Assigns the hand executed with the [`ChangeSignal`] when the
+    /// component prop read by `source` changes.
+    fn on_component_change<
+        C: Component,
+        V: Bindable + PartialEq,
+        S: SystemParam + 'static,
+        F: Fn(&ChangeSignal<V>, &mut StaticSystemParam<S>) + 'static,
+    >(
+        &mut self,
+        source: ComponentReader<C, V>,
+        handler: F,
+    ) -> HandId {
+        self.on_component_change_with(source, Comparator::eq(), handler)
+    }
+
+    /// Watches using `compare` to decide if the prop has changed.
+    fn on_component_change_with<
+        C: Component,
+        V: Bindable,
+        S: SystemParam + 'static,
+        F: Fn(&ChangeSignal<V>, &mut StaticSystemParam<S>) + 'static,
+    >(
+        &mut self,
+        source: ComponentReader<C, V>,
+        compare: Comparator<V>,
+        handler: F,
+    ) -> HandId;
+
+    /// Assigns the hand executed with the [`ChangeSignal`] when the
+    /// resource prop read by `source` changes.
+    fn on_resource_change<
+        R: Resource,
+        V: Bindable + PartialEq,
+        S: SystemParam + 'static,
+        F: Fn(&ChangeSignal<V>, &mut StaticSystemParam<S>) + 'static,
+    >(
+        &mut self,
+        source: ResourceReader<R, V>,
+        handler: F,
+    ) -> HandId {
+        self.on_resource_change_with(source, Comparator::eq(), handler)
+    }
+
+    /// Watches using `compare` to decide if the prop has changed.
+    fn on_resource_change_with<
+        R: Resource,
+        V: Bindable,
+        S: SystemParam + 'static,
+        F: Fn(&ChangeSignal<V>, &mut StaticSystemParam<S>) + 'static,
+    >(
+        &mut self,
+        source: ResourceReader<R, V>,
+        compare: Comparator<V>,
+        handler: F,
+    ) -> HandId;
 }
 
 impl<'w> EntityFlow for EntityWorldMut<'w> {
@@ -520,59 +1036,307 @@ impl<'w> EntityFlow for EntityWorldMut<'w> {
     >(
         &mut self,
         handler: F,
-    ) {
-        let hand = Hand::new(handler);
-        if !self.contains::<Hands<<E as Signal>::Event, S>>() {
-            self.insert((Hands::from(hand), FlowItem));
-        } else {
-            self.get_mut::<Hands<<E as Signal>::Event, S>>()
-                .unwrap()
-                .push(hand);
-        }
+    ) -> HandId {
+        self.add_signal_hand::<E, S>(Hand::new(handler))
+    }
+
+    fn add_signal_hand<E: Signal, S: SystemParam + 'static>(
+        &mut self,
+        hand: Hand<<E as Signal>::Event, S>,
+    ) -> HandId {
+        let id = attach_hand(self, hand);
         self.world_scope(|world| {
             world
                 .resource::<FlowResource>()
                 .register_handle_signals_systems::<E, S>();
         });
+        id
+    }
+
+    fn on_component_change_with<
+        C: Component,
+        V: Bindable,
+        S: SystemParam + 'static,
+        F: Fn(&ChangeSignal<V>, &mut StaticSystemParam<S>) + 'static,
+    >(
+        &mut self,
+        source: ComponentReader<C, V>,
+        compare: Comparator<V>,
+        handler: F,
+    ) -> HandId {
+        let id = self.add_signal_hand::<ChangeSignal<V>, S>(Hand::new(handler));
+        let host = self.id();
+        self.world_scope(|world| add_component_watch(world, source, compare, host, id));
+        id
+    }
+
+    fn on_resource_change_with<
+        R: Resource,
+        V: Bindable,
+        S: SystemParam + 'static,
+        F: Fn(&ChangeSignal<V>, &mut StaticSystemParam<S>) + 'static,
+    >(
+        &mut self,
+        source: ResourceReader<R, V>,
+        compare: Comparator<V>,
+        handler: F,
+    ) -> HandId {
+        let id = self.add_signal_hand::<ChangeSignal<V>, S>(Hand::new(handler));
+        let host = self.id();
+        self.world_scope(|world| add_resource_watch(world, source, compare, host, id));
+        id
+    }
+}
+
+/// Adds the hand to the entity's `Hands` and registers it in the `HandRegistry`.
+fn attach_hand<E: Event, S: SystemParam + 'static>(
+    entity: &mut EntityWorldMut,
+    mut hand: Hand<E, S>,
+) -> HandId {
+    let id = hand.id;
+    hand.phase = entity.world_scope(|world| world.resource::<FlowPhases>().active());
+    if let Some(mut hands) = entity.get_mut::<Hands<E, S>>() {
+        hands.add(hand);
+    } else {
+        entity.insert((Hands::from(hand), FlowItem));
+    }
+    let target = entity.id();
+    entity.world_scope(|world| {
+        world
+            .resource_mut::<HandRegistry>()
+            .0
+            .insert(id, (target, detach_hand::<E, S>));
+    });
+    id
+}
+
+fn detach_hand<E: Event, S: SystemParam + 'static>(
+    world: &mut World,
+    entity: Entity,
+    id: HandId,
+) -> bool {
+    let Some(mut hands) = world.get_mut::<Hands<E, S>>(entity) else {
+        return false;
+    };
+    let len = hands.len();
+    hands.retain(|h| h.id != id);
+    hands.len() != len
+}
+
+/// Maps every assigned hand to its entity and the type-erased remove function.
+#[derive(Resource, Default)]
+struct HandRegistry(HashMap<HandId, (Entity, fn(&mut World, Entity, HandId) -> bool)>);
+
+pub trait Bindable: Send + Sync + Clone + std::fmt::Debug + 'static {}
+impl<T: Send + Sync + Clone + std::fmt::Debug + 'static> Bindable for T {}
+
+/// Decides if the bind target already holds the value, so the write
+/// (and the change notification) can be skipped.
+pub enum Comparator<V> {
+    /// Writes every time the source changes.
+    Always,
+    /// Skips the write if the values are equal (`PartialEq`), the default.
+    Equal(fn(&V, &V) -> bool),
+    /// Skips the write if the values are closer than epsilon, see [`ApproxEq`].
+    Epsilon(f32, fn(&V, &V, f32) -> bool),
+    /// Skips the write if the function returns true.
+    Custom(fn(&V, &V) -> bool),
+}
+
+impl<V> Clone for Comparator<V> {
+    fn clone(&self) -> Self {
+        *self
     }
 }
+impl<V> Copy for Comparator<V> {}
 
-pub trait Bindable: Send + Sync + Clone + std::fmt::Debug + PartialEq + 'static {}
-impl<T: Send + Sync + Clone + PartialEq + std::fmt::Debug + 'static> Bindable for T {}
+impl<V: PartialEq> Comparator<V> {
+    pub fn eq() -> Self {
+        Comparator::Equal(|a, b| a == b)
+    }
+}
+
+impl<V: ApproxEq> Comparator<V> {
+    pub fn epsilon(epsilon: f32) -> Self {
+        Comparator::Epsilon(epsilon, V::approx_eq)
+    }
+}
+
+impl<V> Comparator<V> {
+    /// Returns true if the `current` value is the same as the `value`.
+    pub fn same(&self, current: &V, value: &V) -> bool {
+        match self {
+            Comparator::Always => false,
+            Comparator::Equal(eq) | Comparator::Custom(eq) => eq(current, value),
+            Comparator::Epsilon(epsilon, eq) => eq(current, value, *epsilon),
+        }
+    }
+}
+
+/// Approximate comparison for [`Comparator::epsilon`].
+pub trait ApproxEq {
+    fn approx_eq(&self, other: &Self, epsilon: f32) -> bool;
+}
+
+impl ApproxEq for f32 {
+    fn approx_eq(&self, other: &Self, epsilon: f32) -> bool {
+        (self - other).abs() <= epsilon
+    }
+}
+
+impl ApproxEq for f64 {
+    fn approx_eq(&self, other: &Self, epsilon: f32) -> bool {
+        (self - other).abs() <= epsilon as f64
+    }
+}
+
+impl ApproxEq for Vec2 {
+    fn approx_eq(&self, other: &Self, epsilon: f32) -> bool {
+        self.abs_diff_eq(*other, epsilon)
+    }
+}
+
+impl ApproxEq for Vec3 {
+    fn approx_eq(&self, other: &Self, epsilon: f32) -> bool {
+        self.abs_diff_eq(*other, epsilon)
+    }
+}
+
+impl ApproxEq for Vec4 {
+    fn approx_eq(&self, other: &Self, epsilon: f32) -> bool {
+        self.abs_diff_eq(*other, epsilon)
+    }
+}
+
+impl ApproxEq for Color {
+    fn approx_eq(&self, other: &Self, epsilon: f32) -> bool {
+        Vec4::from(self.as_rgba_f32()).abs_diff_eq(Vec4::from(other.as_rgba_f32()), epsilon)
+    }
+}
 
 pub type ComponentChanges<'w, T> = Res<'w, Channel<ChangedEntity<T>>>;
 type Changes<'w, T, V> = Res<'w, Channel<ApplyChange<T, V>>>;
 
 
+/// The entities with `C` changed during the flow iteration. Entities are
+/// kept sorted and unique. If only some props of the entity are known to be
+/// changed (the bind or hand writes), they are tracked, so the readers marked
+/// with [`ComponentReader::isolated`] are executed only when their prop is
+/// changed. The props with the same root alias each other: writing `bg.r`
+/// changes `bg.hex`. Every other reader of the changed component is executed,
+/// the computed props (`text_len`) may depend on any field.
 #[derive(Resource)]
 pub struct ChangedEntities<C: Component> {
-    entities: HashSet<Entity>,
+    entities: Vec<Entity>,
+    /// Entities missing here are changed entirely.
+    props: HashMap<Entity, Vec<&'static str>>,
     marker: PhantomData<C>,
 }
 impl<C: Component> ChangedEntities<C> {
     fn new() -> Self {
         Self {
-            entities: HashSet::new(),
+            entities: vec![],
+            props: HashMap::new(),
             marker: PhantomData,
         }
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = &Entity> {
+        self.entities.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    /// Returns true if the `prop` of the `entity` was changed, the empty prop
+    /// means any prop.
+    pub fn contains(&self, entity: Entity, prop: &str) -> bool {
+        if self.entities.binary_search(&entity).is_err() {
+            return false;
+        }
+        self.props
+            .get(&entity)
+            .map_or(true, |props| props.iter().any(|p| props_overlap(p, prop)))
+    }
+
+    /// Marks the whole component of the `entity` as changed.
     pub fn add(&mut self, entity: Entity) {
-        self.entities.insert(entity);
+        self.add_prop(entity, "")
+    }
+
+    /// Marks the `prop` of the `entity` as changed, the empty prop marks the
+    /// whole component.
+    pub fn add_prop(&mut self, entity: Entity, prop: &'static str) {
+        match self.entities.binary_search(&entity) {
+            Ok(_) if prop.is_empty() => {
+                self.props.remove(&entity);
+            }
+            Ok(_) => {
+                if let Some(props) = self.props.get_mut(&entity) {
+                    if !props.contains(&prop) {
+                        props.push(prop);
+                    }
+                }
+            }
+            Err(idx) => {
+                self.entities.insert(idx, entity);
+                if !prop.is_empty() {
+                    self.props.insert(entity, vec![prop]);
+                }
+            }
+        }
+    }
+
+    /// Replaces the changes with the (entity, prop) pairs.
+    fn set(&mut self, pairs: &mut Vec<(Entity, &'static str)>) {
+        self.entities.clear();
+        self.props.clear();
+        pairs.sort_unstable();
+        pairs.dedup();
+        for group in pairs.chunk_by(|a, b| a.0 == b.0) {
+            let entity = group[0].0;
+            self.entities.push(entity);
+            // "" sorts first and marks the whole component
+            if !group[0].1.is_empty() {
+                self.props
+                    .insert(entity, group.iter().map(|(_, p)| *p).collect());
+            }
+        }
     }
 }
 
+/// The props overlap if they share the root: `bg.r` overlaps `bg.hex` (both
+/// are computed from `bg`), `items` overlaps `items[3].name`. The empty prop
+/// (the unnamed reader or writer) overlaps everything.
+fn props_overlap(a: &str, b: &str) -> bool {
+    fn root(prop: &str) -> &str {
+        prop.split(['.', '[']).next().unwrap_or(prop)
+    }
+    a.is_empty() || b.is_empty() || root(a) == root(b)
+}
+
 pub struct ChangedEntity<T> {
     entity: Entity,
+    prop: &'static str,
     marker: PhantomData<T>,
 }
 impl<T> ChangedEntity<T> {
     fn new(entity: Entity) -> Self {
         Self {
             entity,
+            prop: "",
             marker: PhantomData,
         }
     }
+    fn with_prop(mut self, prop: &'static str) -> Self {
+        self.prop = prop;
+        self
+    }
 }
 impl<T> From<Entity> for ChangedEntity<T> {
     fn from(value: Entity) -> Self {
@@ -581,22 +1345,31 @@ impl<T> From<Entity> for ChangedEntity<T> {
 }
 
 struct BindSource<S, T, V: Bindable> {
+    /// The flow phase the bind is evaluated in, see [`FlowPhase`].
+    phase: &'static str,
     target: Entity,
     read: Reader<S, V>,
     writer: Writer<T, V>,
-}
+    compare: Comparator<V>,
+    from_prop: &'static str,
+    /// See [`ComponentReader::isolated`].
+    isolated: bool,
+    to_prop: &'static str,
+    order: BindOrder,
+}
+/// Evaluated in the [`BindOrder`], so the changes are sent in the same order every run.
 #[derive(Component)]
 struct ComponentBindSources<S: Component, T: Component, V: Bindable>(
-    HashMap<Entity, Vec<BindSource<S, T, V>>>,
+    BTreeMap<BindOrder, BindSource<S, T, V>>,
 );
 
 #[derive(Resource)]
 struct ResourceBindSources<S: Resource, T: Component, V: Bindable>(
-    HashMap<Entity, Vec<BindSource<S, T, V>>>,
+    BTreeMap<BindOrder, BindSource<S, T, V>>,
 );
 impl<S: Resource, T: Component, V: Bindable> ResourceBindSources<S, T, V> {
     fn new() -> Self {
-        Self(HashMap::new())
+        Self(BTreeMap::new())
     }
 }
 
@@ -616,6 +1389,49 @@ struct ApplyChange<H: Component, V: Bindable> {
     target: Entity,
     writer: Writer<H, V>,
     value: V,
+    compare: Comparator<V>,
+    order: BindOrder,
+    /// The target prop, see [`ComponentWriter::named`] and [`ChangedEntities`]
+    prop: &'static str,
+}
+
+/// The changes of the target with the same key are coalesced.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum WriteKey {
+    /// The component type and the prop path
+    Prop(TypeId, &'static str),
+    /// The writes of the unnamed writer are never coalesced with the other binds
+    Bind(u64),
+}
+
+impl<H: Component, V: Bindable> ApplyChange<H, V> {
+    fn key(&self) -> (Entity, WriteKey) {
+        let key = if self.prop.is_empty() {
+            WriteKey::Bind(self.order.seq)
+        } else {
+            WriteKey::Prop(TypeId::of::<H>(), self.prop)
+        };
+        (self.target, key)
+    }
+}
+
+/// When several binds write the same target prop during the flow iteration,
+/// the bind with the highest priority wins, the latest registered bind wins
+/// between the binds with the same priority.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct BindOrder {
+    priority: i32,
+    seq: u64,
+}
+
+impl BindOrder {
+    fn next(priority: i32) -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        BindOrder {
+            priority,
+            seq: NEXT.fetch_add(1, Ordering::Relaxed),
+        }
+    }
 }
 
 pub trait EntityProp<H: Component, V: Bindable> {
@@ -628,12 +1444,16 @@ impl<H: Component, V: Bindable> EntityProp<H, V> for Entity {
         ComponentReader {
             entity: self.clone(),
             reader: value.into(),
+            prop: "",
+            isolated: false,
         }
     }
     fn set(&self, value: impl Into<Writer<H, V>>) -> ComponentWriter<H, V> {
         ComponentWriter {
             entity: self.clone(),
             writer: value.into(),
+            prop: "",
+            priority: 0,
         }
     }
 }
@@ -643,7 +1463,9 @@ pub struct BindComponentToComponent<S: Component, T: Component, V: Bindable> {
     pub to: ComponentWriter<T, V>,
 }
 
-impl<S: Component, T: Component, V: Bindable> Command for BindComponentToComponent<S, T, V> {
+impl<S: Component, T: Component, V: Bindable + PartialEq> Command
+    for BindComponentToComponent<S, T, V>
+{
     fn apply(self, world: &mut World) {
         world.bind_component_to_component(self.from, self.to)
     }
@@ -654,20 +1476,72 @@ pub struct BindResourceToComponent<S: Resource, T: Component, V: Bindable> {
     pub to: ComponentWriter<T, V>,
 }
 
-impl<S: Resource, T: Component, V: Bindable> Command for BindResourceToComponent<S, T, V> {
+impl<S: Resource, T: Component, V: Bindable + PartialEq> Command
+    for BindResourceToComponent<S, T, V>
+{
     fn apply(self, world: &mut World) {
-        world.bind_resource_to_component(self.from, self.to);
+        world.bind_resource_to_component(self.from.into(), self.to);
     }
 }
 
 pub struct ComponentReader<C: Component, V: Bindable> {
     entity: Entity,
     reader: Reader<C, V>,
+    prop: &'static str,
+    isolated: bool,
+}
+
+impl<C: Component, V: Bindable> ComponentReader<C, V> {
+    /// Labels the source prop for the [`FlowGraph`].
+    pub fn named(mut self, prop: &'static str) -> Self {
+        self.prop = prop;
+        self
+    }
+
+    /// Declares that the named prop has no aliases outside of its root: the
+    /// reader is executed only when the overlapping prop is written, see
+    /// [`ChangedEntities`]. Don't use it for the computed props, the reader of
+    /// `text_len` must run when `text` is written.
+    pub fn isolated(mut self) -> Self {
+        self.isolated = true;
+        self
+    }
+}
+
+pub struct ResourceReader<R: Resource, V: Bindable> {
+    reader: Reader<R, V>,
+    prop: &'static str,
+}
+
+impl<R: Resource, V: Bindable> ResourceReader<R, V> {
+    /// Labels the source prop, the label is only used by [`FlowGraph`]
+    pub fn named(mut self, prop: &'static str) -> Self {
+        self.prop = prop;
+        self
+    }
+}
+
+impl<R: Resource, V: Bindable> From<Reader<R, V>> for ResourceReader<R, V> {
+    fn from(reader: Reader<R, V>) -> Self {
+        ResourceReader { reader, prop: "" }
+    }
+}
+
+impl<R: Resource, V: Bindable> From<Prop<R, V>> for ResourceReader<R, V> {
+    fn from(prop: Prop<R, V>) -> Self {
+        ResourceReader {
+            reader: prop.into(),
+            prop: "",
+        }
+    }
 }
 
 pub enum Reader<H, V: Bindable> {
     Func(fn(&H) -> Value<V>),
     Closure(Rc<dyn Fn(&H) -> Value<V>>),
+    /// Reads the collection item (`list.items[3].name`), returns
+    /// `None` if the item is missing, see [`PropIndex`].
+    Partial(fn(&H) -> Option<V>),
 }
 
 unsafe impl<H, V: Bindable> Send for Reader<H, V> {}
@@ -677,6 +1551,7 @@ impl<H, V: Bindable> Clone for Reader<H, V> {
         match self {
             Self::Func(f) => Self::Func(f.clone()),
             Self::Closure(c) => Self::Closure(c.clone()),
+            Self::Partial(f) => Self::Partial(f.clone()),
         }
     }
 }
@@ -687,10 +1562,12 @@ impl<H, V: Bindable> From<Prop<H, V>> for Reader<H, V> {
     }
 }
 impl<H, V: Bindable> Reader<H, V> {
-    pub fn read<'a>(&self, host: &'a H) -> Value<'a, V> {
+    /// Returns `None` only if the indexed item is missing.
+    pub fn read<'a>(&self, host: &'a H) -> Option<Value<'a, V>> {
         match self {
-            Reader::Func(f) => f(host),
-            Reader::Closure(c) => c(host),
+            Reader::Func(f) => Some(f(host)),
+            Reader::Closure(c) => Some(c(host)),
+            Reader::Partial(f) => f(host).map(Value::Val),
         }
     }
 }
@@ -698,39 +1575,219 @@ impl<H, V: Bindable> Reader<H, V> {
 pub struct ComponentWriter<C: Component, V: Bindable> {
     entity: Entity,
     writer: Writer<C, V>,
+    prop: &'static str,
+    priority: i32,
 }
 
-pub struct Writer<H, V> {
-    get: fn(&H) -> Value<V>,
-    set: fn(&mut H, V),
+impl<C: Component, V: Bindable> ComponentWriter<C, V> {
+    /// Labels the target prop for the [`FlowGraph`]. Only the write of the bind
+    /// with the highest priority is applied when several binds write the same
+    /// named prop, the writes of the unnamed writers are always applied.
+    /// Only the readers of the written prop are notified, see [`ChangedEntities`].
+    pub fn named(mut self, prop: &'static str) -> Self {
+        self.prop = prop;
+        self
+    }
+
+    /// The bind with the higher priority wins when several binds write
+    /// this prop in the same iteration, see [`WorldFlow`] ordering notes.
+    pub fn priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+}
+
+pub enum Writer<H, V> {
+    Func {
+        get: fn(&H) -> Value<V>,
+        set: fn(&mut H, V),
+    },
+    /// Writes the collection item (`list.items[3].name`), the write is
+    /// skipped if the item is missing, see [`PropIndex`].
+    Partial {
+        get: fn(&H) -> Option<V>,
+        set: fn(&mut H, V) -> bool,
+    },
+    /// Transforms the value before the write, see [`MapBack`]. Without `get`
+    /// the current value can't be read, the incoming value is compared with
+    /// the last written one.
+    Closure {
+        get: Option<Rc<dyn Fn(&H) -> V>>,
+        set: Rc<dyn Fn(&mut H, V)>,
+        last: Rc<RefCell<Option<V>>>,
+    },
 }
 impl<H, V: Bindable> Writer<H, V> {
-    pub fn read<'a>(&self, host: &'a H) -> Value<'a, V> {
-        (self.get)(host)
+    pub fn closure(set: impl Fn(&mut H, V) + 'static) -> Self {
+        Writer::Closure {
+            get: None,
+            set: Rc::new(set),
+            last: Rc::new(RefCell::new(None)),
+        }
+    }
+    /// Adds the read side to the closure writer, so unchanged values are not written.
+    pub fn with_read(self, get: impl Fn(&H) -> V + 'static) -> Self {
+        match self {
+            Writer::Closure { set, last, .. } => Writer::Closure {
+                get: Some(Rc::new(get)),
+                set,
+                last,
+            },
+            writer => writer,
+        }
     }
-    pub fn write(&self, host: &mut H, value: V) {
-        (self.set)(host, value)
+    /// Returns `None` if the indexed item is missing. The write-only writer
+    /// returns the last written value, `None` before the first write.
+    pub fn read<'a>(&self, host: &'a H) -> Option<Value<'a, V>> {
+        match self {
+            Writer::Func { get, .. } => Some(get(host)),
+            Writer::Partial { get, .. } => get(host).map(Value::Val),
+            Writer::Closure { get: Some(get), .. } => Some(Value::Val(get(host))),
+            Writer::Closure { last, .. } => last.borrow().clone().map(Value::Val),
+        }
+    }
+    /// Returns false if the value wasn't written because the indexed item is missing.
+    pub fn write(&self, host: &mut H, value: V) -> bool {
+        match self {
+            Writer::Func { set, .. } => {
+                set(host, value);
+                true
+            }
+            Writer::Partial { set, .. } => set(host, value),
+            Writer::Closure { set, last, .. } => {
+                if self.is_write_only() {
+                    *last.borrow_mut() = Some(value.clone());
+                }
+                set(host, value);
+                true
+            }
+        }
+    }
+    /// The closure writer without the read side.
+    pub fn is_write_only(&self) -> bool {
+        matches!(self, Writer::Closure { get: None, .. })
     }
 }
 unsafe impl<H, V: Bindable> Send for Writer<H, V> {}
 unsafe impl<H, V: Bindable> Sync for Writer<H, V> {}
 impl<H, V: Bindable> Clone for Writer<H, V> {
     fn clone(&self) -> Self {
-        Self {
-            get: self.get.clone(),
-            set: self.set.clone(),
+        match self {
+            Writer::Func { get, set } => Writer::Func {
+                get: *get,
+                set: *set,
+            },
+            Writer::Partial { get, set } => Writer::Partial {
+                get: *get,
+                set: *set,
+            },
+            Writer::Closure { get, set, last } => Writer::Closure {
+                get: get.clone(),
+                set: set.clone(),
+                last: last.clone(),
+            },
         }
     }
 }
 impl<H, T: Bindable> From<Prop<H, T>> for Writer<H, T> {
     fn from(prop: Prop<H, T>) -> Self {
-        Writer {
+        Writer::Func {
             get: prop.getter(),
             set: prop.setter(),
         }
     }
 }
 
+/// Collections which items could be bound or assigned by index or key:
+/// `list.items[3].name`, `inventory.slots["gold"]`. Binds skip missing items:
+/// nothing is read from or written to the index out of range.
+pub trait PropIndex<K> {
+    type Item;
+    fn get_at(&self, key: K) -> Option<&Self::Item>;
+    fn get_at_mut(&mut self, key: K) -> Option<&mut Self::Item>;
+}
+
+impl<T> PropIndex<usize> for Vec<T> {
+    type Item = T;
+    fn get_at(&self, key: usize) -> Option<&T> {
+        self.get(key)
+    }
+    fn get_at_mut(&mut self, key: usize) -> Option<&mut T> {
+        self.get_mut(key)
+    }
+}
+
+impl<T> PropIndex<usize> for std::collections::VecDeque<T> {
+    type Item = T;
+    fn get_at(&self, key: usize) -> Option<&T> {
+        self.get(key)
+    }
+    fn get_at_mut(&mut self, key: usize) -> Option<&mut T> {
+        self.get_mut(key)
+    }
+}
+
+impl<T, const N: usize> PropIndex<usize> for [T; N] {
+    type Item = T;
+    fn get_at(&self, key: usize) -> Option<&T> {
+        self.get(key)
+    }
+    fn get_at_mut(&mut self, key: usize) -> Option<&mut T> {
+        self.get_mut(key)
+    }
+}
+
+impl<K: Eq + std::hash::Hash, V> PropIndex<K> for HashMap<K, V> {
+    type Item = V;
+    fn get_at(&self, key: K) -> Option<&V> {
+        self.get(&key)
+    }
+    fn get_at_mut(&mut self, key: K) -> Option<&mut V> {
+        self.get_mut(&key)
+    }
+}
+
+impl<'a, V> PropIndex<&'a str> for HashMap<String, V> {
+    type Item = V;
+    fn get_at(&self, key: &'a str) -> Option<&V> {
+        self.get(key)
+    }
+    fn get_at_mut(&mut self, key: &'a str) -> Option<&mut V> {
+        self.get_mut(key)
+    }
+}
+
+impl<K: Ord, V> PropIndex<K> for std::collections::BTreeMap<K, V> {
+    type Item = V;
+    fn get_at(&self, key: K) -> Option<&V> {
+        self.get(&key)
+    }
+    fn get_at_mut(&mut self, key: K) -> Option<&mut V> {
+        self.get_mut(&key)
+    }
+}
+
+impl<'a, V> PropIndex<&'a str> for std::collections::BTreeMap<String, V> {
+    type Item = V;
+    fn get_at(&self, key: &'a str) -> Option<&V> {
+        self.get(key)
+    }
+    fn get_at_mut(&mut self, key: &'a str) -> Option<&mut V> {
+        self.get_mut(key)
+    }
+}
+
+/// Getters of the indexed item: `.name` in `list.items[3].name` is resolved
+/// without naming the item type.
+pub fn getters_of<T: Construct>(_: &T) -> &'static T::Props<Get> {
+    <T::Props<Get> as Singleton>::instance()
+}
+
+/// Setters of the indexed item, see [`getters_of`].
+pub fn setters_of<T: Construct>(_: &T) -> &'static T::Props<Set> {
+    <T::Props<Set> as Singleton>::instance()
+}
+
 pub trait MapProp<H, V: Bindable> {
     fn map<F: Fn(&V) -> T + 'static, T: Bindable>(self, map: F) -> Reader<H, T>;
 }
@@ -744,11 +1801,189 @@ impl<H: 'static, V: Bindable> MapProp<H, V> for Prop<H, V> {
     }
 }
 
+/// Converts the value on the write side of the bind:
+/// ```ignore
+/// let percent = prop!(Slider.percent).map_back(|s: String| s.parse().unwrap_or(0.));
+/// world.bind_component_to_component(input.get(prop!(Input.text)), slider.set(percent));
+/// ```
+/// The `eml!` binds don't map the target prop, the mapped writers are Rust only.
+pub trait MapBack<H, V: Bindable> {
+    /// Every incoming value is converted and written.
+    fn map_back<F: Fn(T) -> V + 'static, T: Bindable>(self, back: F) -> Writer<H, T>;
+    /// The current value is converted with `map` and compared to the incoming one
+    /// before the write, for the two-way binds between differently typed props.
+    fn map_both<M: Fn(&V) -> T + 'static, F: Fn(T) -> V + 'static, T: Bindable>(
+        self,
+        map: M,
+        back: F,
+    ) -> Writer<H, T>;
+}
+
+impl<H: 'static, V: Bindable> MapBack<H, V> for Prop<H, V> {
+    fn map_back<F: Fn(T) -> V + 'static, T: Bindable>(self, back: F) -> Writer<H, T> {
+        let set = self.setter();
+        Writer::closure(move |host, value| set(host, back(value)))
+    }
+    fn map_both<M: Fn(&V) -> T + 'static, F: Fn(T) -> V + 'static, T: Bindable>(
+        self,
+        map: M,
+        back: F,
+    ) -> Writer<H, T> {
+        let get = self.getter();
+        self.map_back(back)
+            .with_read(move |host| map(get(host).as_ref()))
+    }
+}
+
 pub trait Signal: Send + Sync + Sized + 'static {
     type Event: Event;
     type Descriptor: Singleton;
     type Args: Construct + 'static;
+    /// If true, the signal bubbles up through the `Parent` hierarchy
+    /// after the target hands are executed.
+    const BUBBLES: bool = false;
     fn filter(event: &Self::Event) -> Option<Entity>;
+    /// Returns the id of the only hand the event is addressed to.
+    #[allow(unused_variables)]
+    fn hand(event: &Self::Event) -> Option<HandId> {
+        None
+    }
+}
+
+/// Implemented for signal descriptors (`Signal::Descriptor`), allows
+/// generic hand modifiers like `.on.pressed.once: () => { }`.
+pub trait SignalDescriptor: Singleton + 'static {
+    type Signal: Signal;
+    /// Prepares the entity to receive the signal.
+    #[allow(unused_variables)]
+    fn prepare(entity: &mut EntityWorldMut) {}
+}
+
+/// Hand modifiers of the signal descriptor: `.on.pressed.once`,
+/// `.on.pressed.capture`, `.on.pressed.priority[10]`. Every method starts
+/// from [`HandModifiers::hand_options`] and continues on [`HandOptions`].
+pub trait HandModifiers: SignalDescriptor + Sized {
+    /// The options the modifiers are applied to.
+    fn hand_options(&self) -> HandOptions<Self> {
+        HandOptions::new()
+    }
+
+    /// Executes the hand during the capture phase, before the target hands.
+    fn capture(&self) -> HandOptions<Self> {
+        self.hand_options().capture()
+    }
+
+    /// Removes the hand after the first execution.
+    fn once(&self) -> HandOptions<Self> {
+        self.hand_options().once()
+    }
+
+    /// Hands with higher priority are executed first.
+    fn priority(&self) -> HandPriority<Self> {
+        self.hand_options().priority()
+    }
+
+    /// Executes the hand as the default action of the target.
+    fn default_action(&self) -> HandOptions<Self> {
+        self.hand_options().default_action()
+    }
+}
+
+/// Hand modifiers available from `eml!`, could be chained:
+/// ```ignore
+/// .on.pressed.capture.once: () => { }
+/// .on.pressed.priority[10]: () => { }
+/// .on.pressed.default_action: () => { }
+/// ```
+pub struct HandOptions<D: SignalDescriptor> {
+    capture: bool,
+    default_action: bool,
+    once: bool,
+    priority: i32,
+    marker: PhantomData<D>,
+}
+
+impl<D: SignalDescriptor> Clone for HandOptions<D> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<D: SignalDescriptor> Copy for HandOptions<D> {}
+
+impl<D: SignalDescriptor> Default for HandOptions<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D: SignalDescriptor> HandOptions<D> {
+    pub fn new() -> Self {
+        HandOptions {
+            capture: false,
+            default_action: false,
+            once: false,
+            priority: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// Executes the hand during the capture phase, before the target hands.
+    pub fn capture(mut self) -> Self {
+        self.capture = true;
+        self
+    }
+
+    /// Removes the hand after the first execution.
+    pub fn once(mut self) -> Self {
+        self.once = true;
+        self
+    }
+
+    /// Hands with higher priority are executed first.
+    pub fn priority(self) -> HandPriority<D> {
+        HandPriority(self)
+    }
+
+    /// Executes the hand as the default action of the target, see
+    /// [`Hand::default_action`].
+    pub fn default_action(mut self) -> Self {
+        self.default_action = true;
+        self
+    }
+
+    pub fn assign<
+        'w,
+        S: SystemParam + 'static,
+        F: Fn(&<D::Signal as Signal>::Event, &mut StaticSystemParam<S>) + 'static,
+    >(
+        &self,
+        entity: &mut EntityWorldMut<'w>,
+        func: F,
+    ) -> HandId {
+        D::prepare(entity);
+        let mut hand = Hand::new(func).with_priority(self.priority);
+        if self.capture {
+            hand = hand.capture();
+        }
+        if self.default_action {
+            hand = hand.default_action();
+        }
+        if self.once {
+            hand = hand.once();
+        }
+        entity.add_signal_hand::<D::Signal, S>(hand)
+    }
+}
+
+/// `.priority[10]` part of the `.on.pressed.priority[10]`
+pub struct HandPriority<D: SignalDescriptor>(HandOptions<D>);
+
+impl<D: SignalDescriptor> HandPriority<D> {
+    pub fn at(self, priority: i32) -> HandOptions<D> {
+        let mut options = self.0;
+        options.priority = priority;
+        options
+    }
 }
 
 impl UpdateSignal {
@@ -767,6 +2002,8 @@ impl<E: Event, S: SystemParam + 'static> Handler<E, S> {
     }
 }
 
+/// Hands of the entity ordered by priority, hands with the same priority
+/// are executed in the registration order.
 #[derive(Component, Deref, DerefMut)]
 pub struct Hands<E: Event, S: SystemParam + 'static>(Vec<Hand<E, S>>);
 unsafe impl<E: Event, S: SystemParam> Send for Hands<E, S> {}
@@ -775,17 +2012,125 @@ impl<E: Event, S: SystemParam + 'static> Hands<E, S> {
     pub fn from(hand: Hand<E, S>) -> Self {
         Hands(vec![hand])
     }
+
+    /// Inserts the hand after the hands with the same or higher priority.
+    pub fn add(&mut self, hand: Hand<E, S>) {
+        let idx = self
+            .0
+            .iter()
+            .position(|h| h.priority < hand.priority)
+            .unwrap_or(self.0.len());
+        self.0.insert(idx, hand);
+    }
+}
+
+/// Unique id of the assigned hand, use it to remove the hand
+/// with [`WorldFlow::remove_hand`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HandId(u64);
+
+impl HandId {
+    fn next() -> HandId {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        HandId(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
 }
+
 pub struct Hand<E: Event, S: SystemParam + 'static> {
+    id: HandId,
     func: Handler<E, S>,
+    /// The flow phase the hand is executed in, assigned when the hand is attached.
+    phase: &'static str,
+    capture: bool,
+    default_action: bool,
+    priority: i32,
+    once: bool,
+    fired: Cell<bool>,
 }
 
 impl<E: Event, S: SystemParam> Hand<E, S> {
     pub fn new<F: Fn(&E, &mut StaticSystemParam<S>) + 'static>(func: F) -> Self {
         Self {
+            id: HandId::next(),
             func: Handler(Box::new(func)),
+            phase: FlowPhase::MAIN,
+            capture: false,
+            default_action: false,
+            priority: 0,
+            once: false,
+            fired: Cell::new(false),
         }
     }
+
+    /// Executes the hand during the capture phase, before the target hands.
+    pub fn capture(mut self) -> Self {
+        self.capture = true;
+        self
+    }
+
+    /// Removes the hand after the first execution.
+    pub fn once(mut self) -> Self {
+        self.once = true;
+        self
+    }
+
+    /// Executes the hand only on the target, after the signal went through
+    /// the capture and bubble paths. The hand is executed even if the
+    /// propagation was stopped, [`SignalDispatch::default_prevented`]
+    /// tells if any of the previous hands prevented the default action.
+    pub fn default_action(mut self) -> Self {
+        self.default_action = true;
+        self
+    }
+
+    /// Hands with higher priority are executed first, default is 0.
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn id(&self) -> HandId {
+        self.id
+    }
+
+    pub fn is_capture(&self) -> bool {
+        self.capture
+    }
+
+    pub fn is_default_action(&self) -> bool {
+        self.default_action
+    }
+
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    /// Executes the hand assigned to the `entity`. Returns true if the hand
+    /// is spent and should be removed.
+    pub fn execute(
+        &self,
+        entity: Entity,
+        event: &E,
+        params: &mut StaticSystemParam<S>,
+        stats: &FlowStats,
+    ) -> bool {
+        if self.fired.get() {
+            return false;
+        }
+        if self.once {
+            self.fired.set(true);
+        }
+        let _span = trace_span!(
+            "hand",
+            entity = ?entity,
+            signal = std::any::type_name::<E>(),
+            hand = self.id.0
+        )
+        .entered();
+        stats.hand_executed();
+        self.func.execute(event, params);
+        self.once
+    }
 }
 
 #[derive(Resource, Deref, DerefMut)]
@@ -801,13 +2146,17 @@ fn handle_enters<S: SystemParam + 'static>(
     hands_query: Query<(Entity, &Hands<EnterSignal, S>)>,
     mut new_elements: EventReader<EnterSignal>,
     mut params: StaticSystemParam<S>,
+    mut commands: Commands,
+    phases: Res<FlowPhases>,
+    stats: Res<FlowStats>,
 ) {
-    // let x = params.into_inner()
+    let phase = phases.active();
     for (entity, hands) in hands_query.iter_many(new_elements.read().map(|e| e.entity)) {
         let sig = EnterSignal { entity };
-        hands.iter().for_each(|h| {
-            info!("Handling enter for {entity:?}");
-            h.func.execute(&sig, &mut params);
+        hands.iter().filter(|h| h.phase == phase).for_each(|h| {
+            if h.execute(entity, &sig, &mut params, &stats) {
+                commands.add(RemoveHand(h.id));
+            }
         })
     }
 }
@@ -816,31 +2165,101 @@ fn handle_updates<S: SystemParam + 'static>(
     hands_query: Query<(Entity, &Hands<UpdateSignal, S>)>,
     time: Res<Time>,
     mut params: StaticSystemParam<S>,
+    mut commands: Commands,
+    phases: Res<FlowPhases>,
+    stats: Res<FlowStats>,
 ) {
     let delta = time.delta_seconds();
+    let phase = phases.active();
     for (entity, hands) in hands_query.iter_many(bypass_updates.iter()) {
         let sig = UpdateSignal { entity, delta };
-        hands.iter().for_each(|h| h.func.execute(&sig, &mut params))
+        hands.iter().filter(|h| h.phase == phase).for_each(|h| {
+            if h.execute(entity, &sig, &mut params, &stats) {
+                commands.add(RemoveHand(h.id));
+            }
+        })
     }
 }
 fn handle_signals_system<E: Signal, S: SystemParam + 'static>(
     mut reader: EventReader<E::Event>,
     hands_query: Query<&Hands<E::Event, S>>,
+    parents: Query<&Parent>,
     mut params: StaticSystemParam<S>,
+    mut commands: Commands,
+    stats: Res<FlowStats>,
+    dispatch: Res<SignalDispatch>,
+    _lock: ResMut<SignalDispatchLock>,
+    phases: Res<FlowPhases>,
 ) {
-    // let x = *params;
-    // info!("handling signals systems, num events: {}", reader.len());
-    for (entity, event) in reader.read().filter_map(|e| E::filter(e).map(|n| (n, e))) {
-        info!("handling event on {entity:?}");
-        if let Ok(hands) = hands_query.get(entity) {
+    let phase = phases.active();
+    let mut execute = |entity: Entity, hand: &Hand<E::Event, S>, event: &E::Event| {
+        // the hands of the other phases are executed by their loops
+        if hand.phase != phase || E::hand(event).map_or(false, |id| id != hand.id) {
+            return;
+        }
+        if hand.execute(entity, event, &mut params, &stats) {
+            commands.add(RemoveHand(hand.id));
+        }
+    };
+    for (target, event) in reader.read().filter_map(|e| E::filter(e).map(|n| (n, e))) {
+        // root goes last
+        let ancestors = parents.iter_ancestors(target).collect::<Vec<_>>();
+        let propagation = dispatch.begin(target);
+        let target_hands = hands_query.get(target).ok();
+
+        'propagation: {
+            // capture: from the root down to the target's parent
+            for entity in ancestors.iter().rev().copied() {
+                let Ok(hands) = hands_query.get(entity) else {
+                    continue;
+                };
+                propagation.visit(entity, Phase::Capture);
+                hands
+                    .iter()
+                    .filter(|h| h.capture && !h.default_action)
+                    .for_each(|h| execute(entity, h, event));
+                if propagation.stopped() {
+                    break 'propagation;
+                }
+            }
+
+            // target: both capture and bubble hands
+            if let Some(hands) = target_hands {
+                propagation.visit(target, Phase::Target);
+                hands
+                    .iter()
+                    .filter(|h| !h.default_action)
+                    .for_each(|h| execute(target, h, event));
+            }
+            if !E::BUBBLES || propagation.stopped() {
+                break 'propagation;
+            }
+
+            // bubble: from the target's parent up to the root
+            for entity in ancestors.iter().copied() {
+                let Ok(hands) = hands_query.get(entity) else {
+                    continue;
+                };
+                propagation.visit(entity, Phase::Bubble);
+                hands
+                    .iter()
+                    .filter(|h| !h.capture && !h.default_action)
+                    .for_each(|h| execute(entity, h, event));
+                if propagation.stopped() {
+                    break 'propagation;
+                }
+            }
+        }
+
+        // default: the target's own default action, may check the prevented flag
+        if let Some(hands) = target_hands {
+            propagation.visit(target, Phase::Default);
             hands
                 .iter()
-                .for_each(|h| h.func.execute(event, &mut params));
+                .filter(|h| h.default_action)
+                .for_each(|h| execute(target, h, event));
         }
     }
-    // for hands in hands_query.iter_many(reader.iter().filter_map(|e| E::filter(e))) {
-    //     hands.iter().for_each(|h| h.func.execute(&mut params));
-    // }
 }
 
 #[derive(Event)]
@@ -903,15 +2322,7 @@ impl OnDemandSignal<EnterSignal> {
         entity: &mut EntityWorldMut<'w>,
         func: F,
     ) {
-        let hand = Hand::new(func);
-        if !entity.contains::<Hands<EnterSignal, S>>() {
-            entity.insert((Hands(vec![hand]), FlowItem));
-        } else {
-            entity
-                .get_mut::<Hands<EnterSignal, S>>()
-                .unwrap()
-                .push(hand);
-        }
+        attach_hand(entity, Hand::new(func));
         let id = entity.id();
         entity.world_scope(|world| {
             world
@@ -924,6 +2335,20 @@ impl OnDemandSignal<EnterSignal> {
     }
 }
 
+impl OnDemandSignal<ExitSignal> {
+    pub fn assign<
+        'w,
+        S: SystemParam + 'static,
+        F: Fn(&ExitSignal, &mut StaticSystemParam<S>) + 'static,
+    >(
+        &self,
+        entity: &mut EntityWorldMut<'w>,
+        func: F,
+    ) -> HandId {
+        entity.add_signal_hand::<ExitSignal, S>(Hand::new(func))
+    }
+}
+
 impl OnDemandSignal<UpdateSignal> {
     pub fn assign<
         'w,
@@ -934,18 +2359,7 @@ impl OnDemandSignal<UpdateSignal> {
         entity: &mut EntityWorldMut<'w>,
         func: F,
     ) {
-        let hand = Hand {
-            func: Handler(Box::new(func)),
-        };
-        if !entity.contains::<Hands<UpdateSignal, S>>() {
-            entity.insert((Hands(vec![hand]), FlowItem));
-        } else {
-            entity
-                .get_mut::<Hands<UpdateSignal, S>>()
-                .unwrap()
-                .0
-                .push(hand);
-        }
+        attach_hand(entity, Hand::new(func));
         let id = entity.id();
         entity.world_scope(|world| {
             world.resource_mut::<BypassUpdates>().insert(id);
@@ -958,20 +2372,27 @@ impl OnDemandSignal<UpdateSignal> {
 
 pub struct NotifyChange<C: Component> {
     entity: Entity,
+    prop: &'static str,
     marker: PhantomData<C>,
 }
 impl<C: Component> NotifyChange<C> {
     pub fn new(entity: Entity) -> Self {
         NotifyChange {
             entity,
+            prop: "",
             marker: PhantomData,
         }
     }
+    /// Only the readers of the `prop` will be notified.
+    pub fn prop(mut self, prop: &'static str) -> Self {
+        self.prop = prop;
+        self
+    }
 }
 impl<C: Component> Command for NotifyChange<C> {
     fn apply(self, world: &mut World) {
         if let Some(mut changes) = world.get_resource_mut::<ChangedEntities<C>>() {
-            changes.add(self.entity)
+            changes.add_prop(self.entity, self.prop)
         }
     }
 }
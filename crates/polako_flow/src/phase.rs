@@ -0,0 +1,134 @@
+use bevy::{
+    ecs::schedule::{ScheduleLabel, SystemConfigs},
+    prelude::*,
+    utils::HashMap,
+};
+
+use crate::{flow_loop, FlowResource};
+
+/// The placement of the flow loop: the schedule and the sets it runs after or before.
+/// Every phase runs its own loop:
+/// ```ignore
+/// app.add_plugins(
+///     FlowPlugin::default()
+///         .in_schedule(PostUpdate)
+///         .after(PhysicsSet)
+///         .with_phase(FlowPhase::new("input", PreUpdate)),
+/// );
+/// world.with_flow_phase("input", |world| world.bind_component_to_component(from, to));
+/// ```
+/// Every phase has its own registry of the flow systems. The binds, watchers
+/// and hands remember the phase they were created in and are executed by the
+/// loop of this phase only, so the phases could bind the same types.
+pub struct FlowPhase {
+    name: &'static str,
+    schedule: Box<dyn Fn(&mut App, SystemConfigs) + Send + Sync>,
+    configure: Vec<Box<dyn Fn(SystemConfigs) -> SystemConfigs + Send + Sync>>,
+}
+
+impl FlowPhase {
+    /// The phase every bind and hand is registered in by default.
+    pub const MAIN: &'static str = "main";
+
+    pub fn new(name: &'static str, schedule: impl ScheduleLabel + Clone) -> Self {
+        FlowPhase {
+            name,
+            schedule: Self::add_to(schedule),
+            configure: vec![],
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Moves the phase loop to the `schedule`.
+    pub fn in_schedule(mut self, schedule: impl ScheduleLabel + Clone) -> Self {
+        self.schedule = Self::add_to(schedule);
+        self
+    }
+
+    pub fn after<M>(mut self, set: impl IntoSystemSet<M> + Clone + Send + Sync + 'static) -> Self {
+        self.configure
+            .push(Box::new(move |systems| systems.after(set.clone())));
+        self
+    }
+
+    pub fn before<M>(mut self, set: impl IntoSystemSet<M> + Clone + Send + Sync + 'static) -> Self {
+        self.configure
+            .push(Box::new(move |systems| systems.before(set.clone())));
+        self
+    }
+
+    fn add_to(
+        schedule: impl ScheduleLabel + Clone,
+    ) -> Box<dyn Fn(&mut App, SystemConfigs) + Send + Sync> {
+        Box::new(move |app, systems| {
+            app.add_systems(schedule.clone(), systems);
+        })
+    }
+
+    pub(crate) fn build(&self, app: &mut App) {
+        let name = self.name;
+        let mut systems = (move |world: &mut World| run_flow_phase(world, name)).into_configs();
+        for configure in self.configure.iter() {
+            systems = configure(systems);
+        }
+        (self.schedule)(app, systems);
+        let phases = app.world.resource::<FlowPhases>();
+        if name != phases.active && !phases.inactive.contains_key(name) {
+            let flow = app.world.resource::<FlowResource>().phase();
+            app.world
+                .resource_mut::<FlowPhases>()
+                .inactive
+                .insert(name, flow);
+        }
+    }
+}
+
+/// The flows of the phases which are not running at the moment,
+/// the running one is the `FlowResource`.
+#[derive(Resource)]
+pub(crate) struct FlowPhases {
+    active: &'static str,
+    inactive: HashMap<&'static str, FlowResource>,
+}
+
+impl FlowPhases {
+    pub(crate) fn new() -> Self {
+        FlowPhases {
+            active: FlowPhase::MAIN,
+            inactive: HashMap::new(),
+        }
+    }
+
+    /// The phase the binds and hands are registered in or executed by.
+    pub(crate) fn active(&self) -> &'static str {
+        self.active
+    }
+}
+
+/// Makes the `phase` registry current, returns the previous phase.
+pub(crate) fn enter_flow_phase(world: &mut World, phase: &'static str) -> &'static str {
+    let mut phases = world.resource_mut::<FlowPhases>();
+    let previous = phases.active;
+    if previous == phase {
+        return previous;
+    }
+    let Some(flow) = phases.inactive.remove(phase) else {
+        panic!("Unknown flow phase `{phase}`, add it with FlowPlugin::with_phase");
+    };
+    phases.active = phase;
+    let previous_flow = std::mem::replace(&mut *world.resource_mut::<FlowResource>(), flow);
+    world
+        .resource_mut::<FlowPhases>()
+        .inactive
+        .insert(previous, previous_flow);
+    previous
+}
+
+fn run_flow_phase(world: &mut World, phase: &'static str) {
+    let previous = enter_flow_phase(world, phase);
+    flow_loop(world);
+    enter_flow_phase(world, previous);
+}
@@ -0,0 +1,125 @@
+use std::sync::Mutex;
+
+use bevy::prelude::*;
+
+/// The phase of the signal dispatch, see [`SignalDispatch`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Phase {
+    /// The signal travels from the root down to the target's parent,
+    /// only capture hands are executed.
+    Capture,
+    /// The signal is handled by the target itself.
+    #[default]
+    Target,
+    /// The signal travels from the target's parent up to the root,
+    /// only if the `Signal::BUBBLES` is true.
+    Bubble,
+    /// The default action of the target, executed after the propagation
+    /// even if it was stopped. Default hands should check
+    /// [`SignalDispatch::default_prevented`] before doing their job.
+    Default,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Dispatch {
+    target: Option<Entity>,
+    current: Option<Entity>,
+    phase: Phase,
+    stopped: bool,
+    prevented: bool,
+}
+
+/// DOM-like propagation controls of the signal being dispatched, available
+/// for the hands with `Res<SignalDispatch>`:
+/// ```ignore
+/// .on.pressed: (e) => {
+///     e.stop_propagation();
+///     e.prevent_default();
+/// }
+/// ```
+#[derive(Resource, Default)]
+pub struct SignalDispatch(Mutex<Dispatch>);
+
+/// Taken mutably by every signal handling system, so the dispatches
+/// sharing the [`SignalDispatch`] never run in parallel.
+#[derive(Resource, Default)]
+pub(crate) struct SignalDispatchLock;
+
+impl SignalDispatch {
+    fn get(&self) -> Dispatch {
+        *self.0.lock().unwrap()
+    }
+
+    fn update(&self, func: impl FnOnce(&mut Dispatch)) {
+        func(&mut self.0.lock().unwrap())
+    }
+
+    /// Stops the signal from reaching the rest of the entities
+    /// in the capture or bubble path. The hands of the current entity
+    /// are still executed.
+    pub fn stop_propagation(&self) {
+        self.update(|d| d.stopped = true)
+    }
+
+    pub fn propagation_stopped(&self) -> bool {
+        self.get().stopped
+    }
+
+    /// Marks the signal as handled, so the default hands of the target
+    /// (`.on.pressed.default_action`) could skip the default behavior.
+    pub fn prevent_default(&self) {
+        self.update(|d| d.prevented = true)
+    }
+
+    pub fn default_prevented(&self) -> bool {
+        self.get().prevented
+    }
+
+    pub fn phase(&self) -> Phase {
+        self.get().phase
+    }
+
+    /// The entity the hand is assigned to.
+    pub fn current_target(&self) -> Option<Entity> {
+        self.get().current
+    }
+
+    /// The entity the signal was emitted for.
+    pub fn target(&self) -> Option<Entity> {
+        self.get().target
+    }
+
+    /// Starts the new dispatch for the `target`, dispatch state is
+    /// dropped when returned guard goes out of scope.
+    pub(crate) fn begin(&self, target: Entity) -> Propagation {
+        self.update(|d| {
+            *d = Dispatch {
+                target: Some(target),
+                current: Some(target),
+                ..default()
+            }
+        });
+        Propagation(self)
+    }
+}
+
+pub(crate) struct Propagation<'a>(&'a SignalDispatch);
+
+impl Propagation<'_> {
+    pub(crate) fn visit(&self, entity: Entity, phase: Phase) {
+        self.0.update(|d| {
+            d.current = Some(entity);
+            d.phase = phase;
+        })
+    }
+
+    pub(crate) fn stopped(&self) -> bool {
+        self.0.propagation_stopped()
+    }
+}
+
+impl Drop for Propagation<'_> {
+    fn drop(&mut self) {
+        self.0.update(|d| *d = Dispatch::default())
+    }
+}
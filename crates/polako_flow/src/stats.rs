@@ -0,0 +1,143 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use bevy::prelude::*;
+
+use crate::FlowSet;
+
+const SETS: [FlowSet; 9] = [
+    FlowSet::CleanupChanges,
+    FlowSet::CleanupReaders,
+    FlowSet::CollectChanges,
+    FlowSet::Read,
+    FlowSet::CleanupWriteChanges,
+    FlowSet::Write,
+    FlowSet::HandleSignals,
+    FlowSet::PopulateChanges,
+    FlowSet::Exit,
+];
+
+/// The cost of the flow loops of the single frame.
+#[derive(Debug, Clone, Default)]
+pub struct FlowFrameStats {
+    /// Iterations of all the flow loops (phases) during the frame.
+    pub iterations: usize,
+    /// Bind sources read.
+    pub binds_evaluated: usize,
+    /// Bind targets actually written.
+    pub writes_applied: usize,
+    pub hands_executed: usize,
+    /// Time spent in every `FlowSet`, summed over iterations.
+    pub set_times: Vec<(FlowSet, Duration)>,
+}
+
+impl FlowFrameStats {
+    pub fn total_time(&self) -> Duration {
+        self.set_times.iter().map(|(_, t)| *t).sum()
+    }
+}
+
+/// Flow metrics, updated while the flow runs:
+/// ```ignore
+/// fn report(stats: Res<FlowStats>) {
+///     let frame = stats.last_frame();
+///     if frame.total_time() > Duration::from_millis(2) {
+///         warn!("Slow flow: {frame:?}");
+///     }
+/// }
+/// ```
+/// Run with the `trace` level enabled to get the `bind` and `hand` spans
+/// tagged with the entities and prop names. The `bind` spans are nested in
+/// the `binds` span of the reading system, tagged with the source and target types.
+#[derive(Resource, Default)]
+pub struct FlowStats {
+    iterations: AtomicUsize,
+    binds_evaluated: AtomicUsize,
+    writes_applied: AtomicUsize,
+    hands_executed: AtomicUsize,
+    times: Mutex<SetTimes>,
+    last: FlowFrameStats,
+}
+
+#[derive(Default)]
+struct SetTimes {
+    started: Option<Instant>,
+    times: [Duration; SETS.len()],
+}
+
+impl FlowStats {
+    /// Stats of the previous frame.
+    pub fn last_frame(&self) -> &FlowFrameStats {
+        &self.last
+    }
+
+    /// Stats of the current frame so far.
+    pub fn current_frame(&self) -> FlowFrameStats {
+        let times = self.times.lock().unwrap();
+        FlowFrameStats {
+            iterations: self.iterations.load(Ordering::Relaxed),
+            binds_evaluated: self.binds_evaluated.load(Ordering::Relaxed),
+            writes_applied: self.writes_applied.load(Ordering::Relaxed),
+            hands_executed: self.hands_executed.load(Ordering::Relaxed),
+            set_times: SETS.iter().cloned().zip(times.times).collect(),
+        }
+    }
+
+    pub(crate) fn iteration(&self) {
+        self.iterations.fetch_add(1, Ordering::Relaxed);
+    }
+    pub(crate) fn bind_evaluated(&self) {
+        self.binds_evaluated.fetch_add(1, Ordering::Relaxed);
+    }
+    pub(crate) fn write_applied(&self) {
+        self.writes_applied.fetch_add(1, Ordering::Relaxed);
+    }
+    pub(crate) fn hand_executed(&self) {
+        self.hands_executed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn start(&self) {
+        self.times.lock().unwrap().started = Some(Instant::now());
+    }
+
+    fn stamp(&self, set: usize) {
+        let mut times = self.times.lock().unwrap();
+        let now = Instant::now();
+        if let Some(started) = times.started.replace(now) {
+            times.times[set] += now - started;
+        }
+    }
+
+    fn rotate(&mut self) {
+        self.last = self.current_frame();
+        self.iterations.store(0, Ordering::Relaxed);
+        self.binds_evaluated.store(0, Ordering::Relaxed);
+        self.writes_applied.store(0, Ordering::Relaxed);
+        self.hands_executed.store(0, Ordering::Relaxed);
+        *self.times.lock().unwrap() = SetTimes::default();
+    }
+}
+
+pub(crate) fn rotate_flow_stats(mut stats: ResMut<FlowStats>) {
+    stats.rotate();
+}
+
+/// Adds the systems measuring the time between the `FlowSet`s.
+pub(crate) fn add_stats_systems(schedule: &mut Schedule) {
+    schedule.add_systems(
+        (|stats: Res<FlowStats>| stats.start()).before(FlowSet::CleanupChanges),
+    );
+    for (idx, set) in SETS.iter().enumerate() {
+        let stamp = (move |stats: Res<FlowStats>| stats.stamp(idx)).after(set.clone());
+        if let Some(next) = SETS.get(idx + 1) {
+            schedule.add_systems(stamp.before(next.clone()));
+        } else {
+            schedule.add_systems(stamp);
+        }
+    }
+}
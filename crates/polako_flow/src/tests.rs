@@ -0,0 +1,773 @@
+use polako_constructivism::bridge::ColorProps;
+
+use super::*;
+
+#[derive(Component)]
+struct Level(f32);
+
+#[derive(Component)]
+struct Swatch {
+    bg: Color,
+    size: f32,
+}
+
+#[derive(Component, Default)]
+struct Label {
+    text: String,
+}
+
+fn level_value(host: &Level) -> Value<f32> {
+    Value::Val(host.0)
+}
+
+fn bg_r(host: &Swatch) -> Value<f32> {
+    Value::Val(host.bg.r())
+}
+
+fn set_bg_r(host: &mut Swatch, r: f32) {
+    host.bg.set_r(r);
+}
+
+fn bg_hex(host: &Swatch) -> Value<String> {
+    Value::Val(host.bg.get_hex())
+}
+
+fn label_text(host: &Label) -> Value<String> {
+    Value::Val(host.text.clone())
+}
+
+fn set_label_text(host: &mut Label, text: String) {
+    host.text = text;
+}
+
+fn flow_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(FlowPlugin::default());
+    app
+}
+
+#[test]
+fn test_aliased_prop_readers() {
+    let mut app = flow_app();
+    let world = &mut app.world;
+    let level = world.spawn(Level(0.)).id();
+    let swatch = world.spawn(Swatch {
+        bg: Color::GREEN,
+        size: 1.,
+    }).id();
+    let label = world.spawn(Label::default()).id();
+    // writing `bg.r` changes `bg.hex`
+    world.bind_component_to_component(
+        level.get(Reader::Func(level_value)),
+        swatch
+            .set(Writer::Func {
+                get: bg_r,
+                set: set_bg_r,
+            })
+            .named("bg.r"),
+    );
+    world.bind_component_to_component(
+        swatch.get(Reader::Func(bg_hex)).named("bg.hex"),
+        label
+            .set(Writer::Func {
+                get: label_text,
+                set: set_label_text,
+            })
+            .named("text"),
+    );
+    app.update();
+    assert_eq!("00ff00ff", app.world.get::<Label>(label).unwrap().text);
+
+    app.world.get_mut::<Level>(level).unwrap().0 = 1.;
+    app.update();
+    assert_eq!("ffff00ff", app.world.get::<Label>(label).unwrap().text);
+}
+
+#[test]
+fn test_only_changed_prop_readers() {
+    let mut app = flow_app();
+    let world = &mut app.world;
+    let level = world.spawn(Level(0.)).id();
+    let swatch = world
+        .spawn(Swatch {
+            bg: Color::GREEN,
+            size: 1.,
+        })
+        .id();
+    let label = world.spawn(Label::default()).id();
+    let size_label = world.spawn(Label::default()).id();
+    world.bind_component_to_component(
+        level.get(Reader::Func(level_value)),
+        swatch
+            .set(Writer::Func {
+                get: bg_r,
+                set: set_bg_r,
+            })
+            .named("bg.r"),
+    );
+    world.bind_component_to_component(
+        swatch.get(Reader::Func(bg_hex)).named("bg.hex"),
+        label
+            .set(Writer::Func {
+                get: label_text,
+                set: set_label_text,
+            })
+            .named("text"),
+    );
+    let size_reads = Rc::new(Cell::new(0));
+    let reads = size_reads.clone();
+    world.bind_component_to_component(
+        swatch
+            .get(Reader::Closure(Rc::new(move |host: &Swatch| {
+                reads.set(reads.get() + 1);
+                Value::Val(host.size.to_string())
+            })))
+            .named("size")
+            .isolated(),
+        size_label
+            .set(Writer::Func {
+                get: label_text,
+                set: set_label_text,
+            })
+            .named("text"),
+    );
+    app.update();
+    assert_eq!(1, size_reads.get());
+
+    // only `bg` is written by the bind, the `size` reader is skipped
+    app.world.get_mut::<Level>(level).unwrap().0 = 1.;
+    app.update();
+    assert_eq!("ffff00ff", app.world.get::<Label>(label).unwrap().text);
+    assert_eq!(1, size_reads.get());
+}
+
+fn text_len(host: &Label) -> Value<String> {
+    Value::Val(host.text.len().to_string())
+}
+
+#[test]
+fn test_computed_prop_readers() {
+    let mut app = flow_app();
+    let world = &mut app.world;
+    let level = world.spawn(Level(0.)).id();
+    let label = world.spawn(Label::default()).id();
+    let len_label = world.spawn(Label::default()).id();
+    world.bind_component_to_component(
+        level.get(Reader::Func(level_text)),
+        label
+            .set(Writer::Func {
+                get: label_text,
+                set: set_label_text,
+            })
+            .named("text"),
+    );
+    // `text_len` is computed from `text`, it is not isolated
+    world.bind_component_to_component(
+        label.get(Reader::Func(text_len)).named("text_len"),
+        len_label
+            .set(Writer::Func {
+                get: label_text,
+                set: set_label_text,
+            })
+            .named("text"),
+    );
+    app.update();
+    assert_eq!("1", app.world.get::<Label>(len_label).unwrap().text);
+
+    // only `text` is written by the bind, the `text_len` reader still runs
+    app.world.get_mut::<Level>(level).unwrap().0 = 10.;
+    app.update();
+    assert_eq!("10", app.world.get::<Label>(label).unwrap().text);
+    assert_eq!("2", app.world.get::<Label>(len_label).unwrap().text);
+}
+
+#[derive(Event)]
+struct Ping(Entity);
+
+impl Signal for Ping {
+    type Event = Self;
+    type Args = ();
+    type Descriptor = ();
+    fn filter(event: &Self::Event) -> Option<Entity> {
+        Some(event.0)
+    }
+}
+
+#[derive(Resource, Default)]
+struct Pings(usize);
+
+fn count_ping(_: &Ping, pings: &mut StaticSystemParam<ResMut<'static, Pings>>) {
+    pings.0 += 1;
+}
+
+#[test]
+fn test_phases_execute_hands_once() {
+    let mut app = App::new();
+    app.add_plugins(FlowPlugin::default().with_phase(FlowPhase::new("late", PostUpdate)));
+    app.add_event::<Ping>();
+    app.init_resource::<Pings>();
+    let world = &mut app.world;
+    let main = world.spawn_empty().id();
+    let late = world.spawn_empty().id();
+    world
+        .entity_mut(main)
+        .register_signal_handler::<Ping, ResMut<'static, Pings>, _>(count_ping);
+    world.with_flow_phase("late", |world| {
+        world
+            .entity_mut(late)
+            .register_signal_handler::<Ping, ResMut<'static, Pings>, _>(count_ping);
+    });
+    app.world.send_event(Ping(main));
+    app.world.send_event(Ping(late));
+    app.update();
+    assert_eq!(2, app.world.resource::<Pings>().0);
+}
+
+#[test]
+fn test_phases_bind_the_same_types() {
+    let mut app = App::new();
+    app.add_plugins(FlowPlugin::default().with_phase(FlowPhase::new("late", PostUpdate)));
+    let world = &mut app.world;
+    let level = world.spawn(Level(1.)).id();
+    let main = world.spawn(Label::default()).id();
+    let late = world.spawn(Label::default()).id();
+    world.bind_component_to_component(
+        level.get(Reader::Func(level_text)),
+        main.set(Writer::Func {
+            get: label_text,
+            set: set_label_text,
+        }),
+    );
+    world.with_flow_phase("late", |world| {
+        world.bind_component_to_component(
+            level.get(Reader::Func(level_text)),
+            late.set(Writer::Func {
+                get: label_text,
+                set: set_label_text,
+            }),
+        );
+    });
+    app.update();
+    assert_eq!("1", app.world.get::<Label>(main).unwrap().text);
+    assert_eq!("1", app.world.get::<Label>(late).unwrap().text);
+    // every bind is evaluated by the loop of its own phase only
+    let stats = app.world.resource::<FlowStats>().current_frame();
+    assert_eq!(2, stats.binds_evaluated);
+    assert_eq!(2, stats.writes_applied);
+}
+
+#[test]
+fn test_flow_stats() {
+    let mut app = flow_app();
+    let level = app.world.spawn(Level(1.)).id();
+    let label = app.world.spawn(Label::default()).id();
+    app.world.bind_component_to_component(
+        level.get(Reader::Func(level_text)),
+        label.set(Writer::Func {
+            get: label_text,
+            set: set_label_text,
+        }),
+    );
+    app.update();
+    let frame = app.world.resource::<FlowStats>().current_frame();
+    assert!(frame.iterations > 0);
+    assert_eq!(1, frame.binds_evaluated);
+    assert_eq!(1, frame.writes_applied);
+    assert_eq!(9, frame.set_times.len());
+
+    // the same value is read but not written
+    app.world.get_mut::<Level>(level).unwrap().0 = 1.;
+    app.update();
+    let stats = app.world.resource::<FlowStats>();
+    assert_eq!(1, stats.last_frame().writes_applied);
+    assert_eq!(1, stats.current_frame().binds_evaluated);
+    assert_eq!(0, stats.current_frame().writes_applied);
+
+    // nothing changed
+    app.update();
+    let stats = app.world.resource::<FlowStats>();
+    assert_eq!(1, stats.last_frame().binds_evaluated);
+    assert_eq!(0, stats.current_frame().binds_evaluated);
+    assert_eq!(0, stats.current_frame().writes_applied);
+}
+
+fn defer_exit(e: &ExitSignal, _: &mut StaticSystemParam<()>) {
+    e.defer();
+}
+
+#[test]
+fn test_deferred_exit() {
+    let mut app = flow_app();
+    let world = &mut app.world;
+    let root = world.spawn_empty().id();
+    let child = world.spawn_empty().set_parent(root).id();
+    world
+        .entity_mut(child)
+        .register_signal_handler::<ExitSignal, (), _>(defer_exit);
+    app.update();
+    app.world.exit(root);
+    app.update();
+    assert!(app.world.get_entity(root).is_some());
+    app.world.complete_exit(root);
+    app.update();
+    assert!(app.world.get_entity(root).is_none());
+    assert!(app.world.get_entity(child).is_none());
+}
+
+#[derive(Event)]
+struct Bubble(Entity);
+
+impl Signal for Bubble {
+    type Event = Self;
+    type Args = ();
+    type Descriptor = ();
+    const BUBBLES: bool = true;
+    fn filter(event: &Self::Event) -> Option<Entity> {
+        Some(event.0)
+    }
+}
+
+#[derive(Resource, Default)]
+struct Visits(Vec<(Entity, Phase)>);
+
+type VisitParams = (Res<'static, SignalDispatch>, ResMut<'static, Visits>);
+
+fn visit(_: &Bubble, params: &mut StaticSystemParam<VisitParams>) {
+    let (dispatch, visits) = std::ops::DerefMut::deref_mut(params);
+    visits.0.push((dispatch.current_target().unwrap(), dispatch.phase()));
+}
+
+fn visit_and_stop(event: &Bubble, params: &mut StaticSystemParam<VisitParams>) {
+    visit(event, params);
+    params.0.stop_propagation();
+}
+
+fn visit_and_prevent(event: &Bubble, params: &mut StaticSystemParam<VisitParams>) {
+    visit(event, params);
+    params.0.prevent_default();
+    params.0.stop_propagation();
+}
+
+fn visit_unless_prevented(event: &Bubble, params: &mut StaticSystemParam<VisitParams>) {
+    if !params.0.default_prevented() {
+        visit(event, params);
+    }
+}
+
+fn bubble_app() -> (App, [Entity; 3]) {
+    let mut app = flow_app();
+    app.add_event::<Bubble>();
+    app.init_resource::<Visits>();
+    let world = &mut app.world;
+    let root = world.spawn_empty().id();
+    let mid = world.spawn_empty().set_parent(root).id();
+    let leaf = world.spawn_empty().set_parent(mid).id();
+    for entity in [root, mid] {
+        world
+            .entity_mut(entity)
+            .add_signal_hand::<Bubble, VisitParams>(Hand::new(visit).capture());
+    }
+    (app, [root, mid, leaf])
+}
+
+#[test]
+fn test_signal_propagation() {
+    let (mut app, [root, mid, leaf]) = bubble_app();
+    for entity in [root, mid, leaf] {
+        app.world
+            .entity_mut(entity)
+            .add_signal_hand::<Bubble, VisitParams>(Hand::new(visit));
+    }
+    app.world.send_event(Bubble(leaf));
+    app.update();
+    assert_eq!(
+        vec![
+            (root, Phase::Capture),
+            (mid, Phase::Capture),
+            (leaf, Phase::Target),
+            (mid, Phase::Bubble),
+            (root, Phase::Bubble),
+        ],
+        app.world.resource::<Visits>().0
+    );
+}
+
+#[test]
+fn test_stop_propagation() {
+    let (mut app, [root, mid, leaf]) = bubble_app();
+    app.world
+        .entity_mut(root)
+        .add_signal_hand::<Bubble, VisitParams>(Hand::new(visit));
+    app.world
+        .entity_mut(mid)
+        .add_signal_hand::<Bubble, VisitParams>(Hand::new(visit_and_stop));
+    app.world
+        .entity_mut(leaf)
+        .add_signal_hand::<Bubble, VisitParams>(Hand::new(visit));
+    app.world.send_event(Bubble(leaf));
+    app.update();
+    assert_eq!(
+        vec![
+            (root, Phase::Capture),
+            (mid, Phase::Capture),
+            (leaf, Phase::Target),
+            (mid, Phase::Bubble),
+        ],
+        app.world.resource::<Visits>().0
+    );
+}
+
+#[test]
+fn test_prevent_default() {
+    let (mut app, [root, mid, leaf]) = bubble_app();
+    let mut leaf_entity = app.world.entity_mut(leaf);
+    leaf_entity
+        .add_signal_hand::<Bubble, VisitParams>(Hand::new(visit_unless_prevented).default_action());
+    leaf_entity.add_signal_hand::<Bubble, VisitParams>(Hand::new(visit));
+    app.world.send_event(Bubble(leaf));
+    app.update();
+    assert_eq!(
+        vec![
+            (root, Phase::Capture),
+            (mid, Phase::Capture),
+            (leaf, Phase::Target),
+            (leaf, Phase::Default),
+        ],
+        app.world.resource::<Visits>().0
+    );
+
+    app.world.resource_mut::<Visits>().0.clear();
+    app.world
+        .entity_mut(mid)
+        .add_signal_hand::<Bubble, VisitParams>(Hand::new(visit_and_prevent));
+    app.world.send_event(Bubble(leaf));
+    app.update();
+    assert_eq!(
+        vec![
+            (root, Phase::Capture),
+            (mid, Phase::Capture),
+            (leaf, Phase::Target),
+            (mid, Phase::Bubble),
+        ],
+        app.world.resource::<Visits>().0
+    );
+}
+
+#[derive(Event, Clone)]
+struct Scored(f32);
+
+fn scored_text(event: &Scored) -> Value<String> {
+    Value::Val(event.0.to_string())
+}
+
+#[test]
+fn test_events_last() {
+    let mut app = flow_app();
+    app.add_event::<Scored>();
+    let label = app.world.spawn(Label::default()).id();
+    app.world.bind_events_to_component(
+        EventsReader::last(Reader::Func(scored_text)),
+        label.set(Writer::Func {
+            get: label_text,
+            set: set_label_text,
+        }),
+    );
+    app.update();
+    assert_eq!("", app.world.get::<Label>(label).unwrap().text);
+
+    app.world.send_event(Scored(1.));
+    app.world.send_event(Scored(2.));
+    app.update();
+    assert_eq!("2", app.world.get::<Label>(label).unwrap().text);
+}
+
+#[test]
+fn test_events_fold() {
+    let mut app = flow_app();
+    app.add_event::<Scored>();
+    let level = app.world.spawn(Level(0.)).id();
+    app.world.bind_events_to_component(
+        EventsReader::fold(0., |total: &f32, e: &Scored| total + e.0),
+        level.set(Writer::Func {
+            get: level_value,
+            set: |host: &mut Level, value: f32| host.0 = value,
+        }),
+    );
+    app.world.send_event(Scored(1.));
+    app.world.send_event(Scored(2.));
+    app.update();
+    assert_eq!(3., app.world.get::<Level>(level).unwrap().0);
+
+    app.world.send_event(Scored(4.));
+    app.update();
+    assert_eq!(7., app.world.get::<Level>(level).unwrap().0);
+}
+
+#[test]
+fn test_events_without_add_event() {
+    let mut app = flow_app();
+    let label = app.world.spawn(Label::default()).id();
+    app.world.bind_events_to_component(
+        EventsReader::last(Reader::Func(scored_text)),
+        label.set(Writer::Func {
+            get: label_text,
+            set: set_label_text,
+        }),
+    );
+    app.update();
+    assert_eq!("", app.world.get::<Label>(label).unwrap().text);
+}
+
+fn level_text(host: &Level) -> Value<String> {
+    Value::Val(host.0.to_string())
+}
+
+#[test]
+fn test_binds_coalesce_by_prop() {
+    let mut app = flow_app();
+    let level = app.world.spawn(Level(1.)).id();
+    let label = app.world.spawn(Label::default()).id();
+    // the different writers of the same prop, only the latest registered is applied
+    for prefix in ["a", "b", "c"] {
+        app.world.bind_component_to_component(
+            level.get(Reader::Func(level_text)),
+            label
+                .set(Writer::closure(move |host: &mut Label, value: String| {
+                    host.text.push_str(prefix);
+                    host.text.push_str(&value);
+                }))
+                .named("text"),
+        );
+    }
+    app.update();
+    assert_eq!("c1", app.world.get::<Label>(label).unwrap().text);
+}
+
+#[test]
+fn test_unnamed_writers_are_not_coalesced() {
+    let mut app = flow_app();
+    let level = app.world.spawn(Level(1.)).id();
+    let label = app.world.spawn(Label::default()).id();
+    for prefix in ["a", "b"] {
+        app.world.bind_component_to_component(
+            level.get(Reader::Func(level_text)),
+            label.set(Writer::closure(move |host: &mut Label, value: String| {
+                host.text.push_str(prefix);
+                host.text.push_str(&value);
+            })),
+        );
+    }
+    app.update();
+    assert_eq!("a1b1", app.world.get::<Label>(label).unwrap().text);
+}
+
+#[test]
+fn test_writes_are_applied_in_bind_order() {
+    let mut app = flow_app();
+    let level = app.world.spawn(Level(1.)).id();
+    let swatch = app
+        .world
+        .spawn(Swatch {
+            bg: Color::GREEN,
+            size: 1.,
+        })
+        .id();
+    let label = app.world.spawn(Label::default()).id();
+    let append = |prefix: &'static str| {
+        label.set(Writer::closure(move |host: &mut Label, value: String| {
+            host.text.push_str(prefix);
+            host.text.push_str(&value);
+        }))
+    };
+    // the sources are read by the different systems, the writes are
+    // applied in the registration order anyway
+    app.world
+        .bind_component_to_component(level.get(Reader::Func(level_text)), append("a"));
+    app.world
+        .bind_component_to_component(swatch.get(Reader::Func(bg_hex)), append("b"));
+    app.world
+        .bind_component_to_component(level.get(Reader::Func(level_text)), append("c"));
+    app.update();
+    assert_eq!("a1b00ff00ffc1", app.world.get::<Label>(label).unwrap().text);
+}
+
+#[test]
+fn test_write_only_writer_skips_last_value() {
+    let mut app = flow_app();
+    let level = app.world.spawn(Level(1.)).id();
+    let label = app.world.spawn(Label::default()).id();
+    app.world.bind_component_to_component(
+        level.get(Reader::Func(level_text)),
+        label.set(Writer::closure(|host: &mut Label, value: String| {
+            host.text.push_str(&value);
+        })),
+    );
+    app.update();
+    assert_eq!("1", app.world.get::<Label>(label).unwrap().text);
+
+    // the source is changed, the value is the same
+    app.world.get_mut::<Level>(level).unwrap().0 = 1.;
+    app.update();
+    assert_eq!("1", app.world.get::<Label>(label).unwrap().text);
+
+    app.world.get_mut::<Level>(level).unwrap().0 = 2.;
+    app.update();
+    assert_eq!("12", app.world.get::<Label>(label).unwrap().text);
+}
+
+#[derive(Resource, Default)]
+struct Seen(Vec<(Option<String>, String)>);
+
+fn record_text(e: &ChangeSignal<String>, seen: &mut StaticSystemParam<ResMut<'static, Seen>>) {
+    seen.0.push((e.old.clone(), e.new.clone()));
+}
+
+#[test]
+fn test_first_change_is_not_swallowed() {
+    let mut app = flow_app();
+    app.init_resource::<Seen>();
+    let host = app.world.spawn_empty().id();
+    app.world
+        .entity_mut(host)
+        .on_component_change(host.get(Reader::Func(label_text)), record_text);
+    app.update();
+    assert!(app.world.resource::<Seen>().0.is_empty());
+
+    app.world.entity_mut(host).insert(Label { text: "a".into() });
+    app.update();
+    app.world.get_mut::<Label>(host).unwrap().text = "b".into();
+    app.update();
+    let expected = vec![(None, "a".into()), (Some("a".into()), "b".into())];
+    assert_eq!(expected, app.world.resource::<Seen>().0);
+
+    // the change signals are dropped without `add_event`
+    app.update();
+    app.update();
+    assert!(app.world.resource::<Events<ChangeSignal<String>>>().is_empty());
+}
+
+/// Not `PartialEq`, like `Time`
+#[derive(Resource, Clone, Debug, Default)]
+struct Clock {
+    ticks: u32,
+}
+
+fn count_clock(_: &ChangeSignal<Clock>, pings: &mut StaticSystemParam<ResMut<'static, Pings>>) {
+    pings.0 += 1;
+}
+
+#[test]
+fn test_resource_change() {
+    let mut app = flow_app();
+    app.init_resource::<Clock>();
+    app.init_resource::<Pings>();
+    let host = app.world.spawn_empty().id();
+    ResourceChange::<Clock, Clock>::resource_with(Comparator::Always)
+        .assign(&mut app.world.entity_mut(host), count_clock);
+    app.update();
+    let pings = app.world.resource::<Pings>().0;
+    app.update();
+    assert_eq!(pings, app.world.resource::<Pings>().0);
+
+    app.world.resource_mut::<Clock>().ticks += 1;
+    app.update();
+    assert_eq!(pings + 1, app.world.resource::<Pings>().0);
+}
+
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+struct Score(u32);
+
+fn count_score(_: &ChangeSignal<Score>, pings: &mut StaticSystemParam<ResMut<'static, Pings>>) {
+    pings.0 += 1;
+}
+
+#[test]
+fn test_resource_change_compares_values() {
+    let mut app = flow_app();
+    app.init_resource::<Score>();
+    app.init_resource::<Pings>();
+    let host = app.world.spawn_empty().id();
+    ResourceChange::<Score, Score>::resource().assign(&mut app.world.entity_mut(host), count_score);
+    app.update();
+    let pings = app.world.resource::<Pings>().0;
+
+    // changed to the same value
+    app.world.resource_mut::<Score>().0 = 0;
+    app.update();
+    assert_eq!(pings, app.world.resource::<Pings>().0);
+
+    app.world.resource_mut::<Score>().0 = 1;
+    app.update();
+    assert_eq!(pings + 1, app.world.resource::<Pings>().0);
+}
+
+fn graph_app() -> (App, Entity, Entity) {
+    let mut app = flow_app();
+    let level = app.world.spawn((Level(1.), Name::new("level\t\"one\""))).id();
+    let label = app.world.spawn(Label::default()).id();
+    app.world.bind_component_to_component(
+        level.get(Reader::Func(level_text)).named("value"),
+        label.set(Writer::closure(set_label_text)).named("text"),
+    );
+    app.update();
+    (app, level, label)
+}
+
+#[test]
+fn test_graph_to_dot() {
+    let (app, level, label) = graph_app();
+    let dot = FlowGraph::snapshot(&app.world).to_dot();
+    assert!(dot.starts_with("digraph flow {"));
+    assert!(dot.contains(&format!(
+        "\"{level:?}\" [label=\"level \\\"one\\\" ({level:?})\", shape=ellipse];"
+    )));
+    assert!(dot.contains(&format!(
+        "\"{level:?}\" -> \"{label:?}\" [label=\"value => text: alloc::string::String\"];"
+    )));
+    assert!(!dot.contains('\t'));
+}
+
+#[test]
+fn test_graph_to_json() {
+    let (app, level, label) = graph_app();
+    let json = FlowGraph::snapshot(&app.world).to_json();
+    assert!(json.starts_with("{\"binds\":[{\"source_kind\":\"entity\","));
+    assert!(json.contains(&format!("\"source\":\"level\\u0009\\\"one\\\" ({level:?})\"")));
+    assert!(json.contains("\"source_prop\":\"value\""));
+    assert!(json.contains(&format!("\"target\":\"{label:?}\"")));
+    assert!(json.contains("\"target_prop\":\"text\""));
+    assert!(json.ends_with("],\"hands\":[]}"));
+    assert!(!json.contains('\t'));
+}
+
+#[test]
+fn test_flow_batch_applies_writes_once() {
+    let mut app = flow_app();
+    let level = app.world.spawn(Level(1.)).id();
+    let label = app.world.spawn(Label::default()).id();
+    let writes = Rc::new(Cell::new(0));
+    let counter = writes.clone();
+    app.world.bind_component_to_component(
+        level.get(Reader::Func(level_text)),
+        label.set(Writer::closure(move |host: &mut Label, value: String| {
+            counter.set(counter.get() + 1);
+            host.text = value;
+        })),
+    );
+    app.update();
+    assert_eq!(1, writes.get());
+
+    app.world.flow_batch(|world| {
+        world.get_mut::<Level>(level).unwrap().0 = 2.;
+        // the loop is held until the batch ends
+        flow_loop(world);
+        assert_eq!("1", world.get::<Label>(label).unwrap().text);
+        world.flow_batch(|world| {
+            world.get_mut::<Level>(level).unwrap().0 = 3.;
+        });
+        // the nested batch doesn't release the hold
+        assert_eq!("1", world.get::<Label>(label).unwrap().text);
+    });
+    assert_eq!("3", app.world.get::<Label>(label).unwrap().text);
+    assert_eq!(2, writes.get());
+}
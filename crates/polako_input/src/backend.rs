@@ -0,0 +1,371 @@
+use std::marker::PhantomData;
+
+use bevy::{
+    ecs::system::{StaticSystemParam, SystemParam, SystemParamItem},
+    prelude::*,
+    render::{camera::NormalizedRenderTarget, view::RenderLayers},
+    ui::{CalculatedClip, Node, UiStack},
+    utils::HashMap,
+    window::PrimaryWindow,
+};
+
+use crate::{locate_pointers_system, pointer_input_system, PointerId, PointerLocation};
+
+/// The depth of the bottommost bevy_ui node: ui is drawn above the world.
+/// Every built-in ui backend hits within its own band of `UI_DEPTH` depths,
+/// so the hits of the different backends never interleave.
+pub const UI_DEPTH: f32 = 1_000_000.;
+
+/// The depth of the bottommost `UiSurface` node, the band above the bevy_ui one.
+pub const SURFACE_DEPTH: f32 = 2. * UI_DEPTH;
+
+/// The element under the pointer found by the backend.
+#[derive(Clone, Copy, Debug)]
+pub struct PointerHit {
+    pub entity: Entity,
+    /// The hits with the higher depth are on top.
+    pub depth: f32,
+}
+
+/// Finds the elements under the pointer:
+/// ```ignore
+/// struct CircleBackend;
+/// impl PointerBackend for CircleBackend {
+///     type Param = Query<'static, 'static, (Entity, &'static Circle)>;
+///     fn hit_test(
+///         location: &PointerLocation,
+///         circles: &SystemParamItem<Self::Param>,
+///     ) -> Vec<PointerHit> {
+///         circles
+///             .iter()
+///             .filter(|(_, c)| c.center.distance(location.viewport) <= c.radius)
+///             .map(|(entity, c)| PointerHit { entity, depth: c.depth })
+///             .collect()
+///     }
+/// }
+/// app.add_plugins(PointerBackendPlugin::<CircleBackend>::default());
+/// ```
+/// The hits of every backend are merged by depth, then the `PointerFilter`s
+/// pick the elements receiving the `PointerInput`.
+pub trait PointerBackend: Send + Sync + 'static {
+    type Param: SystemParam + 'static;
+    fn hit_test(
+        location: &PointerLocation,
+        param: &SystemParamItem<Self::Param>,
+    ) -> Vec<PointerHit>;
+}
+
+/// Registers the `B` backend. `UiBackend` is registered by `PolakoInputPlugin`.
+pub struct PointerBackendPlugin<B: PointerBackend>(PhantomData<B>);
+
+impl<B: PointerBackend> Default for PointerBackendPlugin<B> {
+    fn default() -> Self {
+        PointerBackendPlugin(PhantomData)
+    }
+}
+
+impl<B: PointerBackend> Plugin for PointerBackendPlugin<B> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            pointer_backend_system::<B>
+                .after(locate_pointers_system)
+                .before(pointer_input_system),
+        );
+    }
+}
+
+/// The pointers located this frame and the hits reported by the backends.
+#[derive(Resource, Default)]
+pub struct PointerHits {
+    locations: HashMap<PointerId, PointerLocation>,
+    hits: HashMap<PointerId, Vec<PointerHit>>,
+    picked: HashMap<PointerId, Vec<Entity>>,
+}
+
+impl PointerHits {
+    pub fn locations(&self) -> impl Iterator<Item = (PointerId, PointerLocation)> + '_ {
+        self.locations.iter().map(|(pointer, location)| (*pointer, *location))
+    }
+
+    pub fn location(&self, pointer: PointerId) -> Option<PointerLocation> {
+        self.locations.get(&pointer).copied()
+    }
+
+    pub fn push(&mut self, pointer: PointerId, hits: impl IntoIterator<Item = PointerHit>) {
+        self.hits.entry(pointer).or_default().extend(hits);
+    }
+
+    /// The elements under the pointer after the `PointerFilter`s, from the
+    /// topmost. Unlike the `PointerInput`, not affected by the `PointerCapture`.
+    pub fn picked(&self, pointer: PointerId) -> &[Entity] {
+        self.picked.get(&pointer).map_or(&[], |picked| picked.as_slice())
+    }
+
+    pub(crate) fn reset(&mut self, locations: HashMap<PointerId, PointerLocation>) {
+        self.locations = locations;
+        self.hits.clear();
+        self.picked.clear();
+    }
+
+    pub(crate) fn set_picked(&mut self, pointer: PointerId, picked: Vec<Entity>) {
+        self.picked.insert(pointer, picked);
+    }
+
+    /// The hits of every backend from the topmost to the bottommost.
+    pub(crate) fn take(&mut self, pointer: PointerId) -> Vec<PointerHit> {
+        let mut hits = self.hits.remove(&pointer).unwrap_or_default();
+        // stable: the equal depths keep the backend order
+        hits.sort_by(|a, b| b.depth.total_cmp(&a.depth));
+        hits
+    }
+}
+
+pub fn pointer_backend_system<B: PointerBackend>(
+    param: StaticSystemParam<B::Param>,
+    mut hits: ResMut<PointerHits>,
+) {
+    let locations = hits.locations().collect::<Vec<_>>();
+    for (pointer, location) in locations {
+        let found = B::hit_test(&location, &param);
+        hits.push(pointer, found);
+    }
+}
+
+/// Refines the rect of the element for the built-in backends:
+/// ```ignore
+/// commands.entity(button).insert(PointerShape::rounded_rect(8.));
+/// commands.entity(star).insert(PointerShape::polygon(points));
+/// commands.entity(ring).insert(PointerShape::custom(|rel, size| {
+///     (rel - size / 2.).length() > size.x / 4.
+/// }));
+/// ```
+#[derive(Component)]
+pub struct PointerShape(Box<dyn Fn(Vec2, Vec2) -> bool + Send + Sync>);
+
+impl PointerShape {
+    /// The `contains` receives the position relative to the top-left corner
+    /// of the element and the size of the element.
+    pub fn custom(contains: impl Fn(Vec2, Vec2) -> bool + Send + Sync + 'static) -> Self {
+        PointerShape(Box::new(contains))
+    }
+
+    /// The ellipse inscribed into the rect.
+    pub fn ellipse() -> Self {
+        Self::custom(|rel, size| {
+            let radius = size / 2.;
+            radius.cmpgt(Vec2::ZERO).all() && ((rel - radius) / radius).length_squared() <= 1.
+        })
+    }
+
+    pub fn rounded_rect(radius: f32) -> Self {
+        Self::custom(move |rel, size| {
+            let half = size / 2.;
+            let radius = radius.max(0.).min(half.x).min(half.y);
+            // the distance to the rect shrunk by the radius
+            let outside = ((rel - half).abs() - (half - radius)).max(Vec2::ZERO);
+            outside.length() <= radius
+        })
+    }
+
+    /// The polygon with the `points` relative to the top-left corner of the element.
+    pub fn polygon(points: Vec<Vec2>) -> Self {
+        Self::custom(move |rel, _| {
+            // even-odd rule
+            let mut inside = false;
+            for (idx, a) in points.iter().enumerate() {
+                let b = points[(idx + points.len() - 1) % points.len()];
+                if (a.y > rel.y) != (b.y > rel.y)
+                    && rel.x < (b.x - a.x) * (rel.y - a.y) / (b.y - a.y) + a.x
+                {
+                    inside = !inside;
+                }
+            }
+            inside
+        })
+    }
+
+    pub fn contains(&self, rel: Vec2, size: Vec2) -> bool {
+        (self.0)(rel, size)
+    }
+}
+
+/// Hits the bevy_ui nodes in the `UiStack` order.
+pub struct UiBackend;
+
+#[derive(SystemParam)]
+pub struct UiBackendParam<'w, 's> {
+    ui_stack: Res<'w, UiStack>,
+    nodes: Query<
+        'w,
+        's,
+        (
+            &'static Node,
+            &'static GlobalTransform,
+            Option<&'static CalculatedClip>,
+            Option<&'static InheritedVisibility>,
+            Option<&'static PointerShape>,
+        ),
+    >,
+}
+
+impl PointerBackend for UiBackend {
+    type Param = UiBackendParam<'static, 'static>;
+    fn hit_test(
+        location: &PointerLocation,
+        param: &SystemParamItem<Self::Param>,
+    ) -> Vec<PointerHit> {
+        let position = location.viewport;
+        param
+            .ui_stack
+            .uinodes
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, entity)| {
+                let (node, transform, clip, visibility, shape) = param.nodes.get(*entity).ok()?;
+                // Nodes that are not rendered should not be interactable
+                if visibility.map_or(false, |v| !v.get()) {
+                    return None;
+                }
+                let size = node.size();
+                let origin = transform.translation().truncate() - size / 2.;
+                let mut min = origin;
+                let mut max = origin + size;
+                if let Some(clip) = clip {
+                    min = Vec2::max(min, clip.clip.min);
+                    max = Vec2::min(max, clip.clip.max);
+                }
+                let contains_cursor =
+                    (min.x..max.x).contains(&position.x) && (min.y..max.y).contains(&position.y);
+                if !contains_cursor {
+                    return None;
+                }
+                if shape.map_or(false, |s| !s.contains(position - origin, size)) {
+                    return None;
+                }
+                Some(PointerHit {
+                    entity: *entity,
+                    depth: UI_DEPTH + idx as f32,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Hits the `Sprite` bounds in every camera rendering to the window of the
+/// pointer, each sprite is tested against the cameras sharing its `RenderLayers`.
+/// The depth is the z of the sprite, the camera with the higher order wins
+/// when the sprite is rendered by several cameras. Not registered by default:
+/// `app.add_plugins(PointerBackendPlugin::<SpriteBackend>::default())`.
+pub struct SpriteBackend;
+
+#[derive(SystemParam)]
+pub struct SpriteBackendParam<'w, 's> {
+    cameras: Query<
+        'w,
+        's,
+        (
+            &'static Camera,
+            &'static GlobalTransform,
+            Option<&'static RenderLayers>,
+        ),
+    >,
+    primary_window: Query<'w, 's, Entity, With<PrimaryWindow>>,
+    images: Res<'w, Assets<Image>>,
+    sprites: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static Sprite,
+            &'static GlobalTransform,
+            Option<&'static Handle<Image>>,
+            Option<&'static InheritedVisibility>,
+            Option<&'static PointerShape>,
+            Option<&'static RenderLayers>,
+        ),
+    >,
+}
+
+impl PointerBackend for SpriteBackend {
+    type Param = SpriteBackendParam<'static, 'static>;
+    fn hit_test(
+        location: &PointerLocation,
+        param: &SystemParamItem<Self::Param>,
+    ) -> Vec<PointerHit> {
+        let Some(window) = location.window else {
+            return vec![];
+        };
+        let primary = param.primary_window.get_single().ok();
+        let mut cameras = param
+            .cameras
+            .iter()
+            .filter(|(camera, _, _)| {
+                camera.is_active
+                    && matches!(
+                        camera.target.normalize(primary),
+                        Some(NormalizedRenderTarget::Window(target)) if target.entity() == window
+                    )
+            })
+            .collect::<Vec<_>>();
+        // the cameras with the higher order are rendered on top
+        cameras.sort_by_key(|(camera, _, _)| std::cmp::Reverse(camera.order));
+        let mut hits: Vec<PointerHit> = vec![];
+        for (camera, camera_transform, camera_layers) in cameras {
+            let viewport = camera.logical_viewport_rect();
+            if viewport.map_or(false, |rect| !rect.contains(location.abs)) {
+                continue;
+            }
+            let origin = viewport.map_or(Vec2::ZERO, |rect| rect.min);
+            let Some(world) = camera.viewport_to_world_2d(camera_transform, location.abs - origin)
+            else {
+                continue;
+            };
+            let camera_layers = camera_layers.copied().unwrap_or_default();
+            let found = param
+                .sprites
+                .iter()
+                .filter_map(|(entity, sprite, transform, image, visibility, shape, layers)| {
+                    if !camera_layers.intersects(&layers.copied().unwrap_or_default()) {
+                        return None;
+                    }
+                    if hits.iter().any(|hit| hit.entity == entity) {
+                        return None;
+                    }
+                    if visibility.map_or(false, |v| !v.get()) {
+                        return None;
+                    }
+                    let size = sprite
+                        .custom_size
+                        .or_else(|| sprite.rect.map(|r| r.size()))
+                        .or_else(|| {
+                            image
+                                .and_then(|i| param.images.get(i))
+                                .map(|i| i.size_f32())
+                        })?;
+                    let local = transform
+                        .affine()
+                        .inverse()
+                        .transform_point3(world.extend(0.))
+                        .truncate();
+                    let min = (-Vec2::splat(0.5) - sprite.anchor.as_vec()) * size;
+                    let max = min + size;
+                    if local.cmplt(min).any() || local.cmpge(max).any() {
+                        return None;
+                    }
+                    // the top-left corner is the origin, like in ui
+                    let rel = Vec2::new(local.x - min.x, max.y - local.y);
+                    if shape.map_or(false, |s| !s.contains(rel, size)) {
+                        return None;
+                    }
+                    Some(PointerHit {
+                        entity,
+                        depth: transform.translation().z,
+                    })
+                })
+                .collect::<Vec<_>>();
+            hits.extend(found);
+        }
+        hits
+    }
+}
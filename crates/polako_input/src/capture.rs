@@ -0,0 +1,53 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::PointerId;
+
+/// Routes every event of the pointer to the capturing element until released,
+/// so the slider keeps its drag outside of its rect:
+/// ```ignore
+/// fn on_down(e: &PointerInput, capture: &mut ResMut<PointerCapture>) {
+///     capture.capture_pointer(e.pointer, e.entity);
+/// }
+/// ```
+/// The capture is released with the last button up. The element receives
+/// `LostCapture` when the capture is broken: by the other element capturing
+/// the pointer, by the release of the touch or with the last button up.
+#[derive(Resource, Default)]
+pub struct PointerCapture {
+    captures: HashMap<PointerId, Entity>,
+    lost: Vec<(PointerId, Entity)>,
+}
+
+impl PointerCapture {
+    pub fn capture_pointer(&mut self, pointer: PointerId, entity: Entity) {
+        match self.captures.insert(pointer, entity) {
+            Some(previous) if previous != entity => self.lost.push((pointer, previous)),
+            _ => {}
+        }
+    }
+
+    /// Releases the capture without `LostCapture`.
+    pub fn release_pointer(&mut self, pointer: PointerId) {
+        self.captures.remove(&pointer);
+    }
+
+    pub fn captured(&self, pointer: PointerId) -> Option<Entity> {
+        self.captures.get(&pointer).copied()
+    }
+
+    pub(crate) fn break_capture(&mut self, pointer: PointerId) {
+        if let Some(entity) = self.captures.remove(&pointer) {
+            self.lost.push((pointer, entity));
+        }
+    }
+
+    /// Drops the captures of the elements `alive` returns false for, silently.
+    pub(crate) fn retain(&mut self, alive: impl Fn(Entity) -> bool) {
+        self.captures.retain(|_, entity| alive(*entity));
+        self.lost.retain(|(_, entity)| alive(*entity));
+    }
+
+    pub(crate) fn take_lost(&mut self) -> Vec<(PointerId, Entity)> {
+        std::mem::take(&mut self.lost)
+    }
+}
@@ -0,0 +1,293 @@
+use std::marker::PhantomData;
+
+use bevy::{hierarchy::HierarchyQueryExt, prelude::*, ui::Node, utils::HashMap};
+
+use crate::{
+    pointer_input_system, PointerHits, PointerId, PointerInput, PointerInputData,
+    PointerInputPosition, PointerLocation,
+};
+
+/// Registers the drag and drop of the `P` payload:
+/// ```ignore
+/// app.add_plugins(DragDropPlugin::<Card>::default());
+/// commands.entity(card).insert((Card(7), Draggable::<Card>::new().with_preview(ghost)));
+/// commands.entity(slot).insert(DropTarget::<Card>::accept(|card| card.0 < 10));
+/// ```
+pub struct DragDropPlugin<P: Component + Clone>(PhantomData<P>);
+
+impl<P: Component + Clone> Default for DragDropPlugin<P> {
+    fn default() -> Self {
+        DragDropPlugin(PhantomData)
+    }
+}
+
+impl<P: Component + Clone> Plugin for DragDropPlugin<P> {
+    fn build(&self, app: &mut App) {
+        app.add_event::<DragDropInput<P>>();
+        app.add_systems(PreUpdate, drag_drop_system::<P>.after(pointer_input_system));
+    }
+}
+
+/// Makes the element draggable, the `P` component of the element
+/// (or of its nearest draggable ancestor) is the payload.
+#[derive(Component)]
+pub struct Draggable<P: Component + Clone> {
+    preview: Option<Entity>,
+    marker: PhantomData<P>,
+}
+
+impl<P: Component + Clone> Default for Draggable<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P: Component + Clone> Draggable<P> {
+    pub fn new() -> Self {
+        Draggable {
+            preview: None,
+            marker: PhantomData,
+        }
+    }
+
+    /// The ui node shown under the cursor while dragging, hidden otherwise.
+    /// The preview is positioned absolutely within its parent node.
+    /// The preview should not have the `PointerFilter`, or it is the drop target.
+    pub fn with_preview(mut self, preview: Entity) -> Self {
+        self.preview = Some(preview);
+        self
+    }
+
+    pub fn preview(&self) -> Option<Entity> {
+        self.preview
+    }
+}
+
+/// Receives `DragDropInput<P>` while the `P` is dragged over the element
+/// or its descendants.
+#[derive(Component)]
+pub struct DropTarget<P: Component + Clone> {
+    accept: Box<dyn Fn(&P) -> bool + Send + Sync>,
+}
+
+impl<P: Component + Clone> Default for DropTarget<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P: Component + Clone> DropTarget<P> {
+    /// Accepts every payload.
+    pub fn new() -> Self {
+        Self::accept(|_| true)
+    }
+
+    /// Accepts the payload only if `accept` returns true, the rejected
+    /// payload receives `Enter`, `Over` and `Leave` but never `Drop`.
+    pub fn accept(accept: impl Fn(&P) -> bool + Send + Sync + 'static) -> Self {
+        DropTarget {
+            accept: Box::new(accept),
+        }
+    }
+
+    pub fn accepts(&self, payload: &P) -> bool {
+        (self.accept)(payload)
+    }
+}
+
+pub enum DragDropData {
+    Enter,
+    Over,
+    Leave,
+    Drop,
+}
+
+/// Sent to the `DropTarget<P>` while the payload is dragged over it.
+#[derive(Event)]
+pub struct DragDropInput<P: Component + Clone> {
+    pub entity: Entity,
+    /// The dragged element.
+    pub source: Entity,
+    pub pointer: PointerId,
+    pub payload: P,
+    pub accepted: bool,
+    pub position: PointerInputPosition,
+    pub data: DragDropData,
+}
+
+impl<P: Component + Clone> DragDropInput<P> {
+    pub fn payload(&self) -> &P {
+        &self.payload
+    }
+    pub fn source(&self) -> Entity {
+        self.source
+    }
+    pub fn accepted(&self) -> bool {
+        self.accepted
+    }
+    pub fn position(&self) -> PointerInputPosition {
+        self.position
+    }
+    pub fn enter(&self) -> bool {
+        matches!(self.data, DragDropData::Enter)
+    }
+    pub fn over(&self) -> bool {
+        matches!(self.data, DragDropData::Over)
+    }
+    pub fn leave(&self) -> bool {
+        matches!(self.data, DragDropData::Leave)
+    }
+    pub fn dropped(&self) -> bool {
+        matches!(self.data, DragDropData::Drop)
+    }
+}
+
+struct DragSession<P> {
+    source: Entity,
+    payload: P,
+    preview: Option<Entity>,
+    target: Option<(Entity, bool)>,
+    /// The viewport position the target was updated at.
+    position: Option<Vec2>,
+}
+
+/// The target is resolved from the elements under the pointer every frame,
+/// not from the `Drag` events: they are sent to the capturing element
+/// while the pointer is captured.
+pub fn drag_drop_system<P: Component + Clone>(
+    mut sessions: Local<HashMap<PointerId, DragSession<P>>>,
+    mut pointer: EventReader<PointerInput>,
+    hits: Res<PointerHits>,
+    draggables: Query<(&Draggable<P>, &P)>,
+    targets: Query<&DropTarget<P>>,
+    parents: Query<&Parent>,
+    nodes: Query<(&Node, &GlobalTransform)>,
+    mut previews: Query<(&mut Style, &mut Visibility)>,
+    mut events: EventWriter<DragDropInput<P>>,
+) {
+    let mut stops = vec![];
+    // the payload is dragged with the left button only
+    for input in pointer.read().filter(|e| e.button == Some(MouseButton::Left)) {
+        match input.data {
+            PointerInputData::DragStart if !sessions.contains_key(&input.pointer) => {
+                let Some((source, (draggable, payload))) = std::iter::once(input.entity)
+                    .chain(parents.iter_ancestors(input.entity))
+                    .find_map(|e| draggables.get(e).ok().map(|d| (e, d)))
+                else {
+                    continue;
+                };
+                if let Some(preview) = draggable.preview {
+                    let parent = parents.get(preview).ok().and_then(|p| nodes.get(p.get()).ok());
+                    if let Ok((mut style, mut visibility)) = previews.get_mut(preview) {
+                        *visibility = Visibility::Inherited;
+                        place_preview(&mut style, input.position.viewport(), parent);
+                    }
+                }
+                sessions.insert(
+                    input.pointer,
+                    DragSession {
+                        source,
+                        payload: payload.clone(),
+                        preview: draggable.preview,
+                        target: None,
+                        position: None,
+                    },
+                );
+            }
+            PointerInputData::DragStop => stops.push(input.pointer),
+            _ => {}
+        }
+    }
+
+    let mut send = |session: &DragSession<P>,
+                    pointer: PointerId,
+                    (entity, accepted): (Entity, bool),
+                    position: PointerInputPosition,
+                    data: DragDropData| {
+        events.send(DragDropInput {
+            entity,
+            source: session.source,
+            pointer,
+            payload: session.payload.clone(),
+            accepted,
+            position,
+            data,
+        });
+    };
+    let position_at = |location: PointerLocation, target: Option<Entity>| {
+        location.position(target.and_then(|e| nodes.get(e).ok()))
+    };
+    for (pointer, session) in sessions.iter_mut() {
+        let Some(location) = hits.location(*pointer) else {
+            continue;
+        };
+        if let Some(preview) = session.preview {
+            let parent = parents.get(preview).ok().and_then(|p| nodes.get(p.get()).ok());
+            if let Ok((mut style, _)) = previews.get_mut(preview) {
+                place_preview(&mut style, location.viewport, parent);
+            }
+        }
+        // the topmost element with the drop target wins
+        let target = hits.picked(*pointer).iter().find_map(|entity| {
+            std::iter::once(*entity)
+                .chain(parents.iter_ancestors(*entity))
+                .find(|e| targets.contains(*e))
+        });
+        let target = target.and_then(|entity| {
+            let accepted = targets.get(entity).ok()?.accepts(&session.payload);
+            Some((entity, accepted))
+        });
+        let moved = session.position != Some(location.viewport);
+        session.position = Some(location.viewport);
+        if session.target.map(|t| t.0) == target.map(|t| t.0) {
+            if let Some(target) = target.filter(|_| moved) {
+                let position = position_at(location, Some(target.0));
+                send(session, *pointer, target, position, DragDropData::Over);
+            }
+            continue;
+        }
+        if let Some(previous) = session.target.take() {
+            let position = position_at(location, Some(previous.0));
+            send(session, *pointer, previous, position, DragDropData::Leave);
+        }
+        if let Some(target) = target {
+            let position = position_at(location, Some(target.0));
+            send(session, *pointer, target, position, DragDropData::Enter);
+        }
+        session.target = target;
+    }
+    for pointer in stops {
+        let Some(session) = sessions.remove(&pointer) else {
+            continue;
+        };
+        if let Some(preview) = session.preview {
+            if let Ok((_, mut visibility)) = previews.get_mut(preview) {
+                *visibility = Visibility::Hidden;
+            }
+        }
+        let Some(target) = session.target else {
+            continue;
+        };
+        let position = hits
+            .location(pointer)
+            .map_or_else(PointerInputPosition::default, |l| position_at(l, Some(target.0)));
+        let data = if target.1 {
+            DragDropData::Drop
+        } else {
+            DragDropData::Leave
+        };
+        send(&session, pointer, target, position, data);
+    }
+}
+
+/// Moves the preview under the pointer: `left` and `top` of the absolutely
+/// positioned node are relative to its parent node, not to the viewport.
+fn place_preview(style: &mut Style, viewport: Vec2, parent: Option<(&Node, &GlobalTransform)>) {
+    let origin = parent.map_or(Vec2::ZERO, |(node, transform)| {
+        transform.translation().truncate() - node.size() / 2.
+    });
+    let position = viewport - origin;
+    style.position_type = PositionType::Absolute;
+    style.left = Val::Px(position.x);
+    style.top = Val::Px(position.y);
+}
@@ -0,0 +1,277 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use bevy::{
+    input::{keyboard::KeyboardInput, ButtonState},
+    prelude::*,
+    window::ReceivedCharacter,
+};
+use polako_constructivism::bridge::ReadOnly;
+use polako_constructivism::{derive_construct, derive_segment};
+use polako_constructivism::{Get, Singleton};
+
+use crate::{PointerFilter, PointerId, PointerInput, PointerInputData, PointerInputPosition};
+
+/// Makes the element focusable by pointer and by Tab / Shift+Tab, the
+/// element without its own `PointerFilter` gets `PointerFilter::Pass`.
+/// Elements with positive `tab_index` come first (ascending), then the
+/// elements with zero `tab_index` in the tree order. The separate trees
+/// follow the creation order of their first `Focusable`, so the order
+/// doesn't change when the unrelated entities are despawned. Elements with
+/// negative `tab_index` are focusable by pointer only.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Focusable {
+    pub tab_index: i32,
+    /// The creation order.
+    order: u64,
+}
+
+impl Default for Focusable {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl Focusable {
+    pub fn new(tab_index: i32) -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        Focusable {
+            tab_index,
+            order: NEXT.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+
+    pub fn tab_index(&self) -> i32 {
+        self.tab_index
+    }
+
+    pub fn set_tab_index(&mut self, value: i32) {
+        self.tab_index = value
+    }
+}
+
+derive_segment! {
+    seg => Focusable;
+    construct => (tab_index: i32 = 0) -> {
+        Focusable::new(tab_index)
+    };
+    props => {
+        tab_index: i32 = [tab_index, set_tab_index];
+    };
+}
+
+/// The element receiving the keyboard input. Set it to move the focus:
+/// `FocusSignal` is sent to the new element and `BlurSignal` to the old one.
+#[derive(Resource, Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Focused(pub Option<Entity>);
+
+pub enum KeyInputData {
+    Down(KeyCode),
+    Up(KeyCode),
+    Char(char),
+}
+
+/// Keyboard input addressed to the focused element.
+#[derive(Event)]
+pub struct KeyInput {
+    pub entity: Entity,
+    pub data: KeyInputData,
+}
+
+impl KeyInput {
+    /// The pressed or released key, `None` for `Char`.
+    pub fn key(&self) -> Option<KeyCode> {
+        match self.data {
+            KeyInputData::Down(key) | KeyInputData::Up(key) => Some(key),
+            KeyInputData::Char(_) => None,
+        }
+    }
+    /// The typed character, `None` for `Down` and `Up`.
+    pub fn char(&self) -> Option<char> {
+        match self.data {
+            KeyInputData::Char(c) => Some(c),
+            _ => None,
+        }
+    }
+    pub fn key_down(&self) -> bool {
+        matches!(self.data, KeyInputData::Down(_))
+    }
+    pub fn key_up(&self) -> bool {
+        matches!(self.data, KeyInputData::Up(_))
+    }
+}
+
+impl ReadOnly for KeyInput {}
+
+derive_construct! {
+    seq => KeyInput -> Nothing;
+    construct => (entity: Entity, data: KeyInputData) -> {
+        KeyInput { entity, data }
+    };
+    props => {
+        key: Option<KeyCode> = [key, readonly];
+        char: Option<char> = [char, readonly];
+    };
+}
+impl KeyInput {
+    pub fn getters(&self) -> &'static keyinput_construct::Props<Get> {
+        keyinput_construct::Props::instance()
+    }
+}
+
+/// Lets the pointer focus the element: inserts `PointerFilter::Pass` to
+/// the new `Focusable` without its own filter.
+pub fn focusable_filter_system(
+    focusables: Query<Entity, (Added<Focusable>, Without<PointerFilter>)>,
+    mut commands: Commands,
+) {
+    for entity in focusables.iter() {
+        commands.entity(entity).insert(PointerFilter::Pass);
+    }
+}
+
+/// Focuses the nearest focusable ancestor of the pressed element,
+/// pressing outside of the focusable elements clears the focus.
+pub fn pointer_focus_system(
+    mut pointer: EventReader<PointerInput>,
+    mut focused: ResMut<Focused>,
+    focusables: Query<(), With<Focusable>>,
+    parents: Query<&Parent>,
+) {
+    let mut pressed = false;
+    let mut target = None;
+    for event in pointer.read().filter(|e| e.down()) {
+        pressed = true;
+        if target.is_none() {
+            target = std::iter::once(event.entity)
+                .chain(parents.iter_ancestors(event.entity))
+                .find(|e| focusables.contains(*e));
+        }
+    }
+    if pressed && focused.0 != target {
+        focused.0 = target;
+    }
+}
+
+/// Moves the focus with Tab and Shift+Tab.
+pub fn tab_navigation_system(
+    keys: Res<Input<KeyCode>>,
+    mut focused: ResMut<Focused>,
+    focusables: Query<(Entity, &Focusable, Option<&InheritedVisibility>)>,
+    roots: Query<Entity, Without<Parent>>,
+    children: Query<&Children>,
+) {
+    if !keys.just_pressed(KeyCode::Tab) {
+        return;
+    }
+    let backward = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let mut trees = vec![];
+    for root in roots.iter() {
+        let mut tree = vec![];
+        for entity in std::iter::once(root).chain(children.iter_descendants(root)) {
+            let Ok((entity, focusable, visibility)) = focusables.get(entity) else {
+                continue;
+            };
+            if focusable.tab_index < 0 || visibility.map_or(false, |v| !v.get()) {
+                continue;
+            }
+            tree.push((entity, focusable.tab_index, focusable.order));
+        }
+        if let Some(first) = tree.iter().map(|(_, _, order)| *order).min() {
+            trees.push((first, tree));
+        }
+    }
+    // the query yields the roots in the archetype order
+    trees.sort_by_key(|(first, _)| *first);
+    let mut order = trees
+        .into_iter()
+        .flat_map(|(_, tree)| tree)
+        .map(|(entity, tab_index, _)| (entity, tab_index))
+        .collect::<Vec<_>>();
+    if order.is_empty() {
+        return;
+    }
+    // stable: the tree order is kept for the equal indices
+    order.sort_by_key(|(_, tab_index)| if *tab_index == 0 { i32::MAX } else { *tab_index });
+    let current = focused
+        .0
+        .and_then(|f| order.iter().position(|(e, _)| *e == f));
+    let next = match (current, backward) {
+        (None, false) => 0,
+        (None, true) => order.len() - 1,
+        (Some(idx), false) => (idx + 1) % order.len(),
+        (Some(idx), true) => (idx + order.len() - 1) % order.len(),
+    };
+    focused.0 = Some(order[next].0);
+}
+
+/// Sends `Focus` and `Blur` when the `Focused` changes, drops the focus
+/// from despawned or no longer focusable elements.
+pub fn focus_change_system(
+    mut previous: Local<Option<Entity>>,
+    mut focused: ResMut<Focused>,
+    focusables: Query<(), With<Focusable>>,
+    mut events: EventWriter<PointerInput>,
+) {
+    if focused.0.map_or(false, |e| !focusables.contains(e)) {
+        focused.0 = None;
+    }
+    if *previous == focused.0 {
+        return;
+    }
+    if let Some(entity) = previous.filter(|e| focusables.contains(*e)) {
+        events.send(PointerInput {
+            entity,
+            pointer: PointerId::Mouse,
+            button: None,
+            position: PointerInputPosition::default(),
+            data: PointerInputData::Blur,
+        });
+    }
+    if let Some(entity) = focused.0 {
+        events.send(PointerInput {
+            entity,
+            pointer: PointerId::Mouse,
+            button: None,
+            position: PointerInputPosition::default(),
+            data: PointerInputData::Focus,
+        });
+    }
+    *previous = focused.0;
+}
+
+/// Delivers the keyboard input to the focused element. Tab is used
+/// for the navigation and is not delivered.
+pub fn key_input_system(
+    focused: Res<Focused>,
+    mut keyboard: EventReader<KeyboardInput>,
+    mut chars: EventReader<ReceivedCharacter>,
+    mut events: EventWriter<KeyInput>,
+) {
+    let Some(entity) = focused.0 else {
+        keyboard.clear();
+        chars.clear();
+        return;
+    };
+    for input in keyboard.read() {
+        let Some(key) = input.key_code else {
+            continue;
+        };
+        if key == KeyCode::Tab {
+            continue;
+        }
+        let data = match input.state {
+            ButtonState::Pressed => KeyInputData::Down(key),
+            ButtonState::Released => KeyInputData::Up(key),
+        };
+        events.send(KeyInput { entity, data });
+    }
+    for input in chars.read() {
+        if input.char == '\t' {
+            continue;
+        }
+        events.send(KeyInput {
+            entity,
+            data: KeyInputData::Char(input.char),
+        });
+    }
+}
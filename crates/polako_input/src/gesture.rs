@@ -0,0 +1,208 @@
+use std::f32::consts::PI;
+
+use bevy::{prelude::*, ui::UiScale, utils::HashMap};
+use polako_constructivism::bridge::ReadOnly;
+use polako_constructivism::derive_construct;
+use polako_constructivism::{Get, Singleton};
+
+use crate::{PointerId, PointerInput};
+
+/// The touch released faster than this is a swipe candidate, in seconds.
+const SWIPE_MAX_TIME: f32 = 0.5;
+/// The minimal distance the touch should travel to become a swipe, in logical pixels.
+const SWIPE_MIN_DISTANCE: f32 = 50.;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GestureInputData {
+    /// The distance between two touches changed by `scale` times since the last frame.
+    Pinch { scale: f32 },
+    /// Two touches rotated by `angle` radians (clockwise) since the last frame.
+    Rotate { angle: f32 },
+    /// The center of two touches moved by `delta` since the last frame.
+    Pan { delta: Vec2 },
+    /// The fast single touch stroke, `direction` is normalized.
+    Swipe { direction: Vec2, velocity: f32 },
+}
+
+/// The gesture addressed to the element the first touch of the gesture was pressed on.
+#[derive(Event)]
+pub struct GestureInput {
+    pub entity: Entity,
+    pub data: GestureInputData,
+}
+
+impl GestureInput {
+    /// The pinch scale, `1.` for other gestures.
+    pub fn scale(&self) -> f32 {
+        match self.data {
+            GestureInputData::Pinch { scale } => scale,
+            _ => 1.,
+        }
+    }
+    /// The rotation angle, `0.` for other gestures.
+    pub fn angle(&self) -> f32 {
+        match self.data {
+            GestureInputData::Rotate { angle } => angle,
+            _ => 0.,
+        }
+    }
+    /// The pan delta, `Vec2::ZERO` for other gestures.
+    pub fn delta(&self) -> Vec2 {
+        match self.data {
+            GestureInputData::Pan { delta } => delta,
+            _ => Vec2::ZERO,
+        }
+    }
+    /// The swipe direction, `Vec2::ZERO` for other gestures.
+    pub fn direction(&self) -> Vec2 {
+        match self.data {
+            GestureInputData::Swipe { direction, .. } => direction,
+            _ => Vec2::ZERO,
+        }
+    }
+    /// The swipe velocity in logical pixels per second, `0.` for other gestures.
+    pub fn velocity(&self) -> f32 {
+        match self.data {
+            GestureInputData::Swipe { velocity, .. } => velocity,
+            _ => 0.,
+        }
+    }
+}
+
+impl ReadOnly for GestureInput {}
+
+derive_construct! {
+    seq => GestureInput -> Nothing;
+    construct => (entity: Entity, data: GestureInputData) -> {
+        GestureInput { entity, data }
+    };
+    props => {
+        scale: f32 = [scale, readonly];
+        angle: f32 = [angle, readonly];
+        delta: Vec2 = [delta, readonly];
+        direction: Vec2 = [direction, readonly];
+        velocity: f32 = [velocity, readonly];
+    };
+}
+impl GestureInput {
+    pub fn getters(&self) -> &'static gestureinput_construct::Props<Get> {
+        gestureinput_construct::Props::instance()
+    }
+}
+
+#[derive(Default)]
+pub struct GestureSystemState {
+    /// The element and the time every touch was pressed on.
+    touches: HashMap<u64, (Entity, f32)>,
+    two_fingers: Option<TwoFingers>,
+}
+
+struct TwoFingers {
+    ids: (u64, u64),
+    distance: f32,
+    angle: f32,
+    center: Vec2,
+    /// The element the first of two touches was pressed on.
+    target: Entity,
+}
+
+/// Recognizes pinch, rotate and two-finger pan while exactly two touches
+/// are pressed, and swipe when the single touch is released.
+pub fn gesture_system(
+    mut state: Local<GestureSystemState>,
+    mut pointer: EventReader<PointerInput>,
+    touches_input: Res<Touches>,
+    ui_scale: Res<UiScale>,
+    time: Res<Time>,
+    mut events: EventWriter<GestureInput>,
+) {
+    let now = time.elapsed_seconds();
+    for event in pointer.read().filter(|e| e.down()) {
+        let PointerId::Touch(id) = event.pointer else {
+            continue;
+        };
+        // the first down event is the topmost element
+        state.touches.entry(id).or_insert((event.entity, now));
+    }
+    let scale = ui_scale.0 as f32;
+
+    let pressed = touches_input.iter().collect::<Vec<_>>();
+    if let [a, b] = pressed[..] {
+        let (a, b) = if a.id() < b.id() { (a, b) } else { (b, a) };
+        let target = state
+            .touches
+            .get(&a.id())
+            .or_else(|| state.touches.get(&b.id()))
+            .map(|(entity, _)| *entity);
+        let line = (b.position() - a.position()) / scale;
+        let distance = line.length();
+        let angle = line.y.atan2(line.x);
+        let center = (a.position() + b.position()) / 2. / scale;
+        let ids = (a.id(), b.id());
+        if let Some(two) = state.two_fingers.as_mut().filter(|two| two.ids == ids) {
+            let entity = two.target;
+            if two.distance > 0. && distance != two.distance {
+                let data = GestureInputData::Pinch {
+                    scale: distance / two.distance,
+                };
+                events.send(GestureInput { entity, data });
+            }
+            if angle != two.angle {
+                // wrap into -PI..PI
+                let angle = (angle - two.angle + PI).rem_euclid(2. * PI) - PI;
+                let data = GestureInputData::Rotate { angle };
+                events.send(GestureInput { entity, data });
+            }
+            if center != two.center {
+                let data = GestureInputData::Pan {
+                    delta: center - two.center,
+                };
+                events.send(GestureInput { entity, data });
+            }
+            two.distance = distance;
+            two.angle = angle;
+            two.center = center;
+        } else {
+            state.two_fingers = target.map(|target| TwoFingers {
+                ids,
+                distance,
+                angle,
+                center,
+                target,
+            });
+        }
+    } else if pressed.len() > 2 {
+        state.two_fingers = None;
+    }
+
+    for touch in touches_input.iter_just_released() {
+        let id = touch.id();
+        let Some((entity, pressed_at)) = state.touches.remove(&id) else {
+            continue;
+        };
+        // touches of the two finger gesture are never swipes
+        let in_gesture = state
+            .two_fingers
+            .as_ref()
+            .map_or(false, |two| two.ids.0 == id || two.ids.1 == id);
+        if in_gesture {
+            continue;
+        }
+        let stroke = (touch.position() - touch.start_position()) / scale;
+        let duration = now - pressed_at;
+        if duration > SWIPE_MAX_TIME || stroke.length() < SWIPE_MIN_DISTANCE {
+            continue;
+        }
+        let data = GestureInputData::Swipe {
+            direction: stroke.normalize(),
+            velocity: stroke.length() / duration.max(time.delta_seconds()),
+        };
+        events.send(GestureInput { entity, data });
+    }
+    for touch in touches_input.iter_just_canceled() {
+        state.touches.remove(&touch.id());
+    }
+    if touches_input.iter().count() == 0 {
+        state.two_fingers = None;
+    }
+}
@@ -1,29 +1,176 @@
 use bevy::{
-    ecs::query::WorldQuery,
+    ecs::{query::WorldQuery, system::SystemParam},
+    hierarchy::HierarchyQueryExt,
+    input::mouse::MouseWheel,
     prelude::*,
     render::camera::RenderTarget,
     time::Time,
-    ui::{CalculatedClip, Node, UiStack},
+    ui::Node,
+    utils::HashMap,
     window::{PrimaryWindow, Window, WindowRef},
 };
 use polako_constructivism::{Construct, Get, Singleton};
 use polako_constructivism::derive_construct;
 use polako_constructivism::bridge::ReadOnly;
 
+mod backend;
+mod capture;
+mod drag;
+mod focus;
+mod gesture;
+mod scroll;
+
+pub use backend::*;
+pub use capture::*;
+pub use drag::*;
+pub use focus::*;
+pub use gesture::*;
+pub use scroll::*;
+
+#[cfg(test)]
+mod tests;
+
 pub struct PolakoInputPlugin;
 
+/// Thresholds of the pointer recognizers, distances are in logical pixels
+/// and times are in seconds.
+#[derive(Resource, Clone, Debug)]
+pub struct PointerSettings {
+    /// Holding the button for this time starts the drag. The time doesn't
+    /// count while the long press is pending: until the long press or until
+    /// the pointer moves further than `click_distance`.
+    pub drag_time: f32,
+    /// Moving the pressed pointer this far starts the drag. The time and
+    /// the distance sum up: half of the time and half of the distance are enough.
+    pub drag_distance: f32,
+    /// Down and up further than this is not a click.
+    pub click_distance: f32,
+    /// Two clicks on the same element within this time are a double click.
+    pub double_click_interval: f32,
+    /// Holding the pointer without moving for this time is a long press.
+    pub long_press_time: f32,
+}
+
+impl Default for PointerSettings {
+    fn default() -> Self {
+        PointerSettings {
+            drag_time: 0.5,
+            drag_distance: 25.,
+            click_distance: 10.,
+            double_click_interval: 0.4,
+            long_press_time: 0.8,
+        }
+    }
+}
+
 impl Plugin for PolakoInputPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<PointerInput>();
-        app.add_systems(PreUpdate, bypass_filter_system);
-        app.add_systems(PreUpdate, pointer_input_system.after(bypass_filter_system));
+        app.init_resource::<PointerSettings>();
+        app.init_resource::<PointerCapture>();
+        app.add_event::<ScrollInput>();
+        app.init_resource::<PointerHits>();
+        app.add_systems(
+            PreUpdate,
+            (focusable_filter_system, bypass_filter_system).chain(),
+        );
+        app.add_systems(PreUpdate, locate_pointers_system);
+        app.add_systems(
+            PreUpdate,
+            pointer_input_system
+                .after(bypass_filter_system)
+                .after(locate_pointers_system),
+        );
+        app.add_plugins(PointerBackendPlugin::<UiBackend>::default());
+        app.add_event::<KeyInput>();
+        app.init_resource::<Focused>();
+        app.add_systems(
+            PreUpdate,
+            (
+                (pointer_focus_system, tab_navigation_system),
+                focus_change_system,
+                key_input_system,
+            )
+                .chain()
+                .after(pointer_input_system),
+        );
+        app.add_event::<GestureInput>();
+        app.add_systems(PreUpdate, gesture_system.after(pointer_input_system));
     }
 }
 
+/// Logical pixels with the origin at the top-left corner.
 #[derive(Construct, Default, Clone, Copy)]
 pub struct PointerInputPosition {
+    /// The position in the window.
     abs: Vec2,
+    /// The position in the node: `(0, 0)` is the top-left corner of the node.
     rel: Vec2,
+    /// The `rel` divided by the node size: `(1, 1)` is the bottom-right corner.
+    normalized: Vec2,
+    /// The position in the camera viewport with the `UiScale` applied,
+    /// the space of the ui nodes.
+    viewport: Vec2,
+    window: Option<Entity>,
+    camera: Option<Entity>,
+}
+
+impl PointerInputPosition {
+    pub fn abs(&self) -> Vec2 {
+        self.abs
+    }
+    pub fn rel(&self) -> Vec2 {
+        self.rel
+    }
+    pub fn normalized(&self) -> Vec2 {
+        self.normalized
+    }
+    pub fn viewport(&self) -> Vec2 {
+        self.viewport
+    }
+    pub fn window(&self) -> Option<Entity> {
+        self.window
+    }
+    pub fn camera(&self) -> Option<Entity> {
+        self.camera
+    }
+}
+
+/// Where the pointer is: the window, the camera and the positions.
+#[derive(Clone, Copy, Debug)]
+pub struct PointerLocation {
+    pub window: Option<Entity>,
+    /// The ui camera rendering to the window under the pointer.
+    pub camera: Option<Entity>,
+    /// The position in the window.
+    pub abs: Vec2,
+    /// The position in the camera viewport with the `UiScale` applied.
+    pub viewport: Vec2,
+}
+
+impl PointerLocation {
+    /// The position relative to the node, or to the viewport if `node` is `None`.
+    pub fn position(&self, node: Option<(&Node, &GlobalTransform)>) -> PointerInputPosition {
+        let (rel, normalized) = if let Some((node, transform)) = node {
+            let size = node.size();
+            let rel = self.viewport - (transform.translation().truncate() - size / 2.);
+            let normalized = Vec2::new(
+                if size.x > 0. { rel.x / size.x } else { 0. },
+                if size.y > 0. { rel.y / size.y } else { 0. },
+            );
+            (rel, normalized)
+        } else {
+            (self.viewport, Vec2::ZERO)
+        };
+        PointerInputPosition {
+            abs: self.abs,
+            rel,
+            normalized,
+            viewport: self.viewport,
+            window: self.window,
+            camera: self.camera,
+        }
+    }
 }
 
 #[derive(Construct)]
@@ -33,6 +180,7 @@ pub struct PointerInputDrag {
     source_entities: Vec<Entity>,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum PointerInputData {
     Up,
     Down,
@@ -40,13 +188,59 @@ pub enum PointerInputData {
     DragStart,
     Drag,
     DragStop,
+    /// Down and up on the same element without moving further than
+    /// `PointerSettings::click_distance`.
+    Click,
+    /// The second click with the same button on the same element within
+    /// `PointerSettings::double_click_interval`, sent after the `Click`.
+    DoubleClick,
+    /// The pointer is held without moving for `PointerSettings::long_press_time`,
+    /// the click is not sent after the long press.
+    LongPress,
+    /// The pointer entered the element or one of its descendants, doesn't bubble.
+    Enter,
+    /// The pointer left the element and all of its descendants, doesn't bubble.
+    Leave,
+    /// The pointer entered the element, bubbles.
+    Over,
+    /// The pointer left the element, bubbles.
+    Out,
+    /// Sent right after every `Enter` for the code written before the
+    /// `Enter`, `Leave`, `Over` and `Out` were introduced.
+    #[deprecated(note = "use `Enter` or `Over` instead")]
     Hover,
     Focus,
+    Blur,
+    /// The element captured the pointer lost the capture, see `PointerCapture`.
+    LostCapture,
+}
+
+impl PointerInputData {
+    /// True for the data caused by the button: down, up, drag, click and long press.
+    pub fn is_button(&self) -> bool {
+        use PointerInputData::*;
+        matches!(
+            self,
+            Up | Down | DragStart | Drag | DragStop | Click | DoubleClick | LongPress
+        )
+    }
+}
+
+/// Identifies the pointer: the mouse or the touch (by the touch id).
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PointerId {
+    #[default]
+    Mouse,
+    Touch(u64),
 }
 
 #[derive(Event)]
 pub struct PointerInput {
     pub entity: Entity,
+    pub pointer: PointerId,
+    /// The button of the button data (see `PointerInputData::is_button`),
+    /// touches are reported as `MouseButton::Left`.
+    pub button: Option<MouseButton>,
     pub position: PointerInputPosition,
     pub data: PointerInputData,
 }
@@ -55,6 +249,12 @@ impl PointerInput {
     pub fn position(&self) -> PointerInputPosition {
         self.position
     }
+    pub fn pointer(&self) -> PointerId {
+        self.pointer
+    }
+    pub fn button(&self) -> Option<MouseButton> {
+        self.button
+    }
     pub fn up(&self) -> bool {
         match self.data {
             PointerInputData::Up => true,
@@ -74,10 +274,19 @@ impl PointerInput {
         }
     }
     pub fn hover(&self) -> bool {
-        match self.data {
-            PointerInputData::Hover => true,
-            _ => false,
-        }
+        self.enter()
+    }
+    pub fn enter(&self) -> bool {
+        matches!(self.data, PointerInputData::Enter)
+    }
+    pub fn leave(&self) -> bool {
+        matches!(self.data, PointerInputData::Leave)
+    }
+    pub fn over(&self) -> bool {
+        matches!(self.data, PointerInputData::Over)
+    }
+    pub fn out(&self) -> bool {
+        matches!(self.data, PointerInputData::Out)
     }
     pub fn focus(&self) -> bool {
         match self.data {
@@ -85,6 +294,21 @@ impl PointerInput {
             _ => false,
         }
     }
+    pub fn blur(&self) -> bool {
+        matches!(self.data, PointerInputData::Blur)
+    }
+    pub fn lost_capture(&self) -> bool {
+        matches!(self.data, PointerInputData::LostCapture)
+    }
+    pub fn click(&self) -> bool {
+        matches!(self.data, PointerInputData::Click)
+    }
+    pub fn double_click(&self) -> bool {
+        matches!(self.data, PointerInputData::DoubleClick)
+    }
+    pub fn long_press(&self) -> bool {
+        matches!(self.data, PointerInputData::LongPress)
+    }
     pub fn drag_start(&self) -> bool {
         match self.data {
             PointerInputData::DragStart => true,
@@ -112,11 +336,19 @@ impl ReadOnly for PointerInput { }
 
 derive_construct! {
     seq => PointerInput -> Nothing;
-    construct => (entity: Entity, position: PointerInputPosition, data: PointerInputData) -> {
-        PointerInput { entity, position, data }
+    construct => (
+        entity: Entity,
+        pointer: PointerId,
+        button: Option<MouseButton>,
+        position: PointerInputPosition,
+        data: PointerInputData
+    ) -> {
+        PointerInput { entity, pointer, button, position, data }
     };
     props => {
         position: PointerInputPosition = construct;
+        pointer: PointerId = [pointer, readonly];
+        button: Option<MouseButton> = [button, readonly];
         motion: bool = [motion, readonly];
     };
 }
@@ -147,21 +379,233 @@ pub enum ActivePointerFilter {
 #[world_query(mutable)]
 pub struct PointerQuery {
     entity: Entity,
-    node: &'static Node,
-    global_transform: &'static GlobalTransform,
+    node: Option<&'static Node>,
+    global_transform: Option<&'static GlobalTransform>,
     filter: Option<&'static ActivePointerFilter>,
-    calculated_clip: Option<&'static CalculatedClip>,
-    computed_visibility: Option<&'static InheritedVisibility>,
+    scrollable: Option<&'static Scrollable>,
 }
 
+/// The state of the every pointer: the mouse and every touch.
 #[derive(Default)]
 pub struct PointerSystemState {
+    pointers: HashMap<PointerId, PointerState>,
+}
+
+#[derive(Default)]
+struct PointerState {
+    last_cursor_position: Option<Vec2>,
+    /// The hit targets, receive `Over` and `Out`
+    over: Vec<Entity>,
+    /// The hit targets and their ancestors, receive `Enter` and `Leave`
+    hovered: Vec<Entity>,
+    /// In the order the buttons were pressed
+    buttons: Vec<ButtonState>,
+    /// The button, the elements, the time and the position of the last click
+    last_click: Option<(MouseButton, Vec<Entity>, f32, Vec2)>,
+}
+
+/// The state of the single pressed button of the pointer.
+struct ButtonState {
+    button: MouseButton,
     pressed_entities: Vec<Entity>,
-    drag_in_seconds: Option<f32>,
+    /// The drag starts when it reaches `1.`
+    drag_progress: f32,
     dragging_from: Vec<Entity>,
     press_position: Option<Vec2>,
-    last_cursor_position: Option<Vec2>,
     dragging: bool,
+    pressed_at: f32,
+    long_pressed: bool,
+}
+
+impl ButtonState {
+    fn new(button: MouseButton, press_position: Option<Vec2>, now: f32) -> Self {
+        ButtonState {
+            button,
+            pressed_entities: vec![],
+            drag_progress: 0.,
+            dragging_from: vec![],
+            press_position,
+            dragging: false,
+            pressed_at: now,
+            long_pressed: false,
+        }
+    }
+}
+
+/// The pointer input of the single frame.
+struct PointerFrame {
+    cursor_position: Option<Vec2>,
+    hits: Vec<Entity>,
+    hovered: Vec<Entity>,
+    pressed: Vec<MouseButton>,
+    released: Vec<MouseButton>,
+    delta_seconds: f32,
+    /// `Time::elapsed_seconds`
+    now: f32,
+}
+
+type PointerEvents = Vec<(Entity, PointerInputData, Option<MouseButton>)>;
+
+impl PointerState {
+    /// Returns the events in the order they should be sent.
+    fn update(&mut self, frame: PointerFrame, settings: &PointerSettings) -> PointerEvents {
+        let PointerFrame {
+            cursor_position,
+            hits,
+            hovered,
+            pressed,
+            released,
+            delta_seconds,
+            now,
+        } = frame;
+        let mut events = vec![];
+        for button in pressed.iter().copied() {
+            self.buttons.retain(|b| b.button != button);
+            self.buttons.push(ButtonState::new(button, cursor_position, now));
+        }
+        let delta = match (cursor_position, self.last_cursor_position) {
+            (Some(c), Some(l)) => c - l,
+            _ => Vec2::ZERO,
+        };
+        self.last_cursor_position = cursor_position;
+
+        for entity in self.over.iter().copied().filter(|e| !hits.contains(e)) {
+            events.push((entity, PointerInputData::Out, None));
+        }
+        // from the deepest element to the root
+        for entity in self.hovered.iter().copied().filter(|e| !hovered.contains(e)) {
+            events.push((entity, PointerInputData::Leave, None));
+        }
+        for entity in hits.iter().copied().filter(|e| !self.over.contains(e)) {
+            events.push((entity, PointerInputData::Over, None));
+        }
+        // from the root to the deepest element
+        for entity in hovered.iter().rev().copied().filter(|e| !self.hovered.contains(e)) {
+            events.push((entity, PointerInputData::Enter, None));
+            #[allow(deprecated)]
+            events.push((entity, PointerInputData::Hover, None));
+        }
+        self.over = hits.clone();
+        self.hovered = hovered;
+
+        let Some(position) = cursor_position else {
+            // released outside of the windows: finish the drag only
+            for state in self.buttons.iter().filter(|b| released.contains(&b.button)) {
+                if state.dragging {
+                    for entity in state.dragging_from.iter().copied() {
+                        events.push((entity, PointerInputData::DragStop, Some(state.button)));
+                    }
+                }
+            }
+            self.buttons.retain(|b| !released.contains(&b.button));
+            return events;
+        };
+
+        let mut drag_start = vec![];
+        for state in self.buttons.iter_mut() {
+            if pressed.contains(&state.button) {
+                state.pressed_entities = hits.clone();
+                for entity in hits.iter().copied() {
+                    events.push((entity, PointerInputData::Down, Some(state.button)));
+                }
+            }
+            let near_press = state
+                .press_position
+                .map_or(false, |p| position.distance(p) <= settings.click_distance);
+            if state.long_pressed || !near_press {
+                state.drag_progress += delta_seconds / settings.drag_time.max(f32::EPSILON);
+            }
+            state.drag_progress += delta.length() / settings.drag_distance.max(f32::EPSILON);
+            if !state.dragging && !state.pressed_entities.is_empty() && state.drag_progress >= 1. {
+                state.dragging = true;
+                state.dragging_from = state.pressed_entities.clone();
+                drag_start.push(state.button);
+            }
+        }
+        if delta != Vec2::ZERO && !self.buttons.iter().any(|b| b.dragging) {
+            for entity in hits.iter().copied() {
+                events.push((entity, PointerInputData::Motion, None));
+            }
+        }
+
+        for state in self.buttons.iter_mut() {
+            let button = Some(state.button);
+            let up = released.contains(&state.button);
+            let near_press = state
+                .press_position
+                .map_or(false, |p| position.distance(p) <= settings.click_distance);
+            if drag_start.contains(&state.button) {
+                for entity in state.dragging_from.iter().copied() {
+                    events.push((entity, PointerInputData::DragStart, button));
+                }
+            }
+            if state.dragging && !up && delta != Vec2::ZERO {
+                for entity in hits.iter().copied() {
+                    events.push((entity, PointerInputData::Drag, button));
+                }
+            }
+            if state.dragging && up {
+                let dropped = hits.iter().filter(|e| !state.dragging_from.contains(e));
+                for entity in state.dragging_from.iter().chain(dropped).copied() {
+                    events.push((entity, PointerInputData::DragStop, button));
+                }
+            }
+            if up {
+                for entity in hits.iter().copied() {
+                    events.push((entity, PointerInputData::Up, button));
+                }
+            }
+            if !state.long_pressed
+                && !state.dragging
+                && !state.pressed_entities.is_empty()
+                && near_press
+                && now - state.pressed_at >= settings.long_press_time
+            {
+                state.long_pressed = true;
+                for entity in state.pressed_entities.iter().copied() {
+                    events.push((entity, PointerInputData::LongPress, button));
+                }
+            }
+            if !up || !near_press || state.long_pressed || state.dragging {
+                continue;
+            }
+            let double_clicked = match self.last_click.take() {
+                Some((last_button, last, at, last_position))
+                    if last_button == state.button
+                        && now - at <= settings.double_click_interval
+                        && position.distance(last_position) <= settings.click_distance =>
+                {
+                    last
+                }
+                _ => vec![],
+            };
+            let mut clicked = vec![];
+            for entity in hits.iter().copied() {
+                if !state.pressed_entities.contains(&entity) {
+                    continue;
+                }
+                events.push((entity, PointerInputData::Click, button));
+                if double_clicked.contains(&entity) {
+                    // the third click starts the new double click
+                    events.push((entity, PointerInputData::DoubleClick, button));
+                } else {
+                    clicked.push(entity);
+                }
+            }
+            if !clicked.is_empty() {
+                self.last_click = Some((state.button, clicked, now, position));
+            }
+        }
+        self.buttons.retain(|b| !released.contains(&b.button));
+        events
+    }
+
+    /// `Out` and `Leave` for the pointer which is gone (the touch is released).
+    fn release(self) -> PointerEvents {
+        let out = self.over.into_iter().map(|e| (e, PointerInputData::Out, None));
+        let leave = self.hovered.into_iter().map(|e| (e, PointerInputData::Leave, None));
+        out.chain(leave).collect()
+    }
 }
 
 pub fn bypass_filter_system(
@@ -169,7 +613,7 @@ pub fn bypass_filter_system(
     mut commands: Commands,
 ) {
     for (entity, filter) in nodes.iter() {
-        info!("Bypassing {filter:?} for {entity:?}");
+        trace!("Bypassing {filter:?} for {entity:?}");
         match filter {
             PointerFilter::Pass => commands.entity(entity).insert(ActivePointerFilter::Pass),
             PointerFilter::Block => commands.entity(entity).insert(ActivePointerFilter::Block),
@@ -178,236 +622,268 @@ pub fn bypass_filter_system(
     }
 }
 
+/// Finds the window, the camera and the viewport position of the pointer.
+#[derive(SystemParam)]
+pub struct PointerLocator<'w, 's> {
+    cameras: Query<'w, 's, (Entity, &'static Camera, Option<&'static UiCameraConfig>)>,
+    primary_window: Query<'w, 's, (Entity, &'static Window), With<PrimaryWindow>>,
+    windows: Query<'w, 's, (Entity, &'static Window), Without<PrimaryWindow>>,
+    ui_scale: Res<'w, UiScale>,
+}
+
+impl PointerLocator<'_, '_> {
+    fn primary(&self) -> Option<Entity> {
+        self.primary_window.get_single().ok().map(|(entity, _)| entity)
+    }
+
+    /// The first ui camera rendering to the `window` with the `abs` in its viewport.
+    fn locate(&self, window: Entity, abs: Vec2) -> Option<PointerLocation> {
+        let is_ui_disabled =
+            |camera_ui| matches!(camera_ui, Some(&UiCameraConfig { show_ui: false, .. }));
+        self.cameras
+            .iter()
+            .filter(|(_, _, camera_ui)| !is_ui_disabled(*camera_ui))
+            .find_map(|(entity, camera, _)| {
+                let target = match camera.target {
+                    RenderTarget::Window(WindowRef::Primary) => self.primary()?,
+                    RenderTarget::Window(WindowRef::Entity(window)) => window,
+                    _ => return None,
+                };
+                if target != window {
+                    return None;
+                }
+                let viewport = camera.logical_viewport_rect();
+                if viewport.map_or(false, |rect| !rect.contains(abs)) {
+                    return None;
+                }
+                let origin = viewport.map(|rect| rect.min).unwrap_or_default();
+                Some(PointerLocation {
+                    window: Some(window),
+                    camera: Some(entity),
+                    abs,
+                    viewport: (abs - origin) / self.ui_scale.0 as f32,
+                })
+            })
+    }
+
+    /// The mouse cursor in the focused window.
+    fn mouse(&self) -> Option<PointerLocation> {
+        self.primary_window
+            .iter()
+            .chain(self.windows.iter())
+            .filter(|(_, window)| window.focused)
+            .find_map(|(entity, window)| self.locate(entity, window.cursor_position()?))
+    }
+
+    /// Touches are reported in the primary window.
+    fn touch(&self, abs: Vec2) -> Option<PointerLocation> {
+        let primary = self.primary();
+        primary
+            .and_then(|window| self.locate(window, abs))
+            .or(Some(PointerLocation {
+                window: primary,
+                camera: None,
+                abs,
+                viewport: abs / self.ui_scale.0 as f32,
+            }))
+    }
+}
+
+/// Locates the mouse and every touch (the released this frame too) for the backends.
+pub fn locate_pointers_system(
+    locator: PointerLocator,
+    touches_input: Res<Touches>,
+    mut hits: ResMut<PointerHits>,
+) {
+    let mut locations = HashMap::new();
+    if let Some(location) = locator.mouse() {
+        locations.insert(PointerId::Mouse, location);
+    }
+    let touches = touches_input
+        .iter()
+        .chain(touches_input.iter_just_released())
+        .chain(touches_input.iter_just_canceled());
+    for touch in touches {
+        if let Some(location) = locator.touch(touch.position()) {
+            locations.insert(PointerId::Touch(touch.id()), location);
+        }
+    }
+    hits.reset(locations);
+}
+
 // pointer_input_system is the rewriten bevy's ui_focus_system
 // it emit PointerEvent with associated entities and data.
+// Every pointer (the mouse and every touch) is tracked independently.
 pub fn pointer_input_system(
     mut state: Local<PointerSystemState>,
-    camera: Query<(&Camera, Option<&UiCameraConfig>)>,
-    primary_window: Query<&Window, With<PrimaryWindow>>,
-    windows: Query<&Window, Without<PrimaryWindow>>,
+    mut pointer_hits: ResMut<PointerHits>,
     mouse_button_input: Res<Input<MouseButton>>,
     touches_input: Res<Touches>,
-    ui_stack: Res<UiStack>,
     time: Res<Time>,
+    settings: Res<PointerSettings>,
+    mut capture: ResMut<PointerCapture>,
     pointer_query: Query<PointerQuery>,
+    parents: Query<&Parent>,
+    mut wheel: EventReader<MouseWheel>,
+    keys: Res<Input<KeyCode>>,
     mut events: EventWriter<PointerInput>,
+    mut scroll_events: EventWriter<ScrollInput>,
 ) {
-    let up =
-        mouse_button_input.just_released(MouseButton::Left) || touches_input.any_just_released();
-    let down =
-        mouse_button_input.just_pressed(MouseButton::Left) || touches_input.any_just_pressed();
-
-    let is_ui_disabled =
-        |camera_ui| matches!(camera_ui, Some(&UiCameraConfig { show_ui: false, .. }));
+    capture.retain(|entity| pointer_query.contains(entity));
 
-    let cursor_position = camera
-        .iter()
-        .filter(|(_, camera_ui)| !is_ui_disabled(*camera_ui))
-        .filter_map(|(camera, _)| {
-            if let RenderTarget::Window(window_ref) = camera.target {
-                Some(window_ref)
-            } else {
-                None
-            }
-        })
-        .filter_map(|window_ref| {
-            if let WindowRef::Entity(entity) = window_ref {
-                windows.get(entity).ok()
-            } else {
-                primary_window.get_single().ok()
-            }
-        })
-        .filter(|window| window.focused)
-        .find_map(|window| window.cursor_position())
-        .or_else(|| touches_input.first_pressed_position());
-
-    if down {
-        state.press_position = cursor_position;
-        state.drag_in_seconds = Some(0.5);
-    }
-    let delta = match (cursor_position, state.last_cursor_position) {
-        (Some(c), Some(l)) => c - l,
-        _ => Vec2::ZERO,
-    };
-
-    state.last_cursor_position = cursor_position;
-    let mut moused_over_nodes = ui_stack
-        .uinodes
-        .iter()
-        // reverse the iterator to traverse the tree from closest nodes to furthest
-        .rev()
-        .filter_map(|entity| {
-            if let Ok(node) = pointer_query.get(*entity) {
-                // Nodes that are not rendered should not be interactable
-                if let Some(computed_visibility) = node.computed_visibility {
-                    if !computed_visibility.get() {
-                        return None;
-                    }
-                }
-
-                let position = node.global_transform.translation();
-                let ui_position = position.truncate();
-                let extents = node.node.size() / 2.0;
-                let mut min = ui_position - extents;
-                let mut max = ui_position + extents;
-                if let Some(clip) = node.calculated_clip {
-                    min = Vec2::max(min, clip.clip.min);
-                    max = Vec2::min(max, clip.clip.max);
-                }
-                // if the current cursor position is within the bounds of the node, consider it for
-                // emiting the event
-                let contains_cursor = if let Some(cursor_position) = cursor_position {
-                    (min.x..max.x).contains(&cursor_position.x)
-                        && (min.y..max.y).contains(&cursor_position.y)
-                } else {
-                    false
-                };
-
-                if contains_cursor {
-                    Some(*entity)
-                } else {
-                    None
-                }
-            } else {
-                None
-            }
-        })
-        .collect::<Vec<Entity>>()
-        .into_iter();
-    let mut down_entities = vec![];
-    let mut up_entities = vec![];
-    let mut pressed_entities = vec![];
-    let mut drag_entities = vec![];
-    let mut motion_entities = vec![];
-    let mut drag_start_entities = vec![];
-    let delta_len = delta.length();
-    if let Some(drag_in_seconds) = &mut state.drag_in_seconds {
-        *drag_in_seconds -= time.delta_seconds();
-        *drag_in_seconds -= delta_len / 50.;
-    }
-    if !state.dragging
-        && !state.pressed_entities.is_empty()
-        && state.drag_in_seconds.is_some()
-        && state.drag_in_seconds.unwrap() <= 0.
-    {
-        state.dragging = true;
-        drag_start_entities = state.pressed_entities.clone();
+    // (pointer, pressed buttons, released buttons)
+    let mut pointers = vec![];
+    pointers.push((
+        PointerId::Mouse,
+        mouse_button_input.get_just_pressed().copied().collect::<Vec<_>>(),
+        mouse_button_input.get_just_released().copied().collect::<Vec<_>>(),
+    ));
+    // touches are the left button
+    for touch in touches_input.iter() {
+        let pressed = touches_input.just_pressed(touch.id());
+        pointers.push((
+            PointerId::Touch(touch.id()),
+            pressed.then_some(MouseButton::Left).into_iter().collect(),
+            vec![],
+        ));
     }
-    let send_drag_stop = state.dragging && up;
-    let mut drag_stop_entities = vec![];
-    if send_drag_stop {
-        drag_stop_entities = state.dragging_from.clone();
+    let mut released_touches = vec![];
+    for touch in touches_input
+        .iter_just_released()
+        .chain(touches_input.iter_just_canceled())
+    {
+        let id = PointerId::Touch(touch.id());
+        pointers.push((id, vec![], vec![MouseButton::Left]));
+        released_touches.push(id);
     }
 
-    let mut iter = pointer_query.iter_many(moused_over_nodes.by_ref());
-    while let Some(node) = iter.fetch_next() {
-        if node.filter.is_none() {
-            continue;
+    let mut send = |pointer: PointerId, location: Option<PointerLocation>, inputs: PointerEvents| {
+        for (entity, data, button) in inputs {
+            let position = location.map_or_else(PointerInputPosition::default, |location| {
+                let node = pointer_query
+                    .get(entity)
+                    .ok()
+                    .and_then(|n| n.node.zip(n.global_transform));
+                location.position(node)
+            });
+            events.send(PointerInput {
+                entity,
+                pointer,
+                button,
+                position,
+                data,
+            });
         }
-        let entity = node.entity;
+    };
 
-        if down {
-            state.pressed_entities.push(entity);
-            down_entities.push(entity);
+    let mut mouse_hit = None;
+    for (pointer, pressed, released) in pointers {
+        let location = pointer_hits.location(pointer);
+        let cursor_position = location.map(|l| l.viewport);
+        let picked = pick(pointer_hits.take(pointer), &pointer_query);
+        pointer_hits.set_picked(pointer, picked.clone());
+        // the captured pointer is always over the capturing element
+        let mut hits = match capture.captured(pointer) {
+            Some(entity) => vec![entity],
+            None => picked,
+        };
+        if pointer == PointerId::Mouse {
+            mouse_hit = location.zip(hits.first().copied());
         }
-        if up {
-            up_entities.push(entity);
-            let pressed_entity_idx = state.pressed_entities.iter().position(|e| *e == entity);
-            if let Some(pressed_entity_idx) = pressed_entity_idx {
-                state.pressed_entities.remove(pressed_entity_idx);
-                pressed_entities.push(entity);
+        // every picked element and its ancestors receive the non-bubbling `Enter`
+        let mut hovered = vec![];
+        for entity in hits.iter().copied() {
+            for entity in std::iter::once(entity).chain(parents.iter_ancestors(entity)) {
+                if !hovered.contains(&entity) {
+                    hovered.push(entity);
+                }
             }
         }
-        if delta != Vec2::ZERO {
-            if state.dragging {
-                drag_entities.push(entity);
-            } else {
-                motion_entities.push(entity);
-            };
-        }
-        if send_drag_stop {
-            drag_stop_entities.push(entity);
-        }
-
-        match node.filter.unwrap() {
-            ActivePointerFilter::Block => {
-                break;
-            }
-            ActivePointerFilter::Pass => { /* allow the next node to be processed */ }
+        // the picked ancestors receive the bubbling events from the deepest elements
+        let nested = hits
+            .iter()
+            .flat_map(|e| parents.iter_ancestors(*e))
+            .collect::<Vec<_>>();
+        hits.retain(|e| !nested.contains(e));
+        let any_released = !released.is_empty();
+        let frame = PointerFrame {
+            cursor_position,
+            hits,
+            hovered,
+            pressed,
+            released,
+            delta_seconds: time.delta_seconds(),
+            now: time.elapsed_seconds(),
+        };
+        let pointer_state = state.pointers.entry(pointer).or_default();
+        let inputs = pointer_state.update(frame, &settings);
+        if any_released && pointer_state.buttons.is_empty() {
+            capture.break_capture(pointer);
         }
+        send(pointer, location, inputs);
     }
 
-    let Some(pos) = cursor_position else { return };
-    if down_entities.len() > 0 {
-        // TODO: do not forget about drag_in_seconds here
-        // state.was_down_at = time.elapsed_seconds();
-        for entity in down_entities.iter().copied() {
-            events.send(PointerInput {
-                entity,
-                // TODO: do not forget about calculating screen/windown/viewport/relative position
-                position: PointerInputPosition { abs: pos, rel: pos },
-                data: PointerInputData::Down,
-            });
+    for pointer in released_touches {
+        capture.break_capture(pointer);
+        if let Some(pointer_state) = state.pointers.remove(&pointer) {
+            send(pointer, None, pointer_state.release());
         }
     }
-
-    for entity in motion_entities.iter().copied() {
-        info!("sending PointerInput::hover event");
-        events.send(PointerInput {
-            entity,
-            // TODO: do not forget about calculating screen/windown/viewport/relative position
-            position: PointerInputPosition { abs: pos, rel: pos },
-            // delta,
-            data: PointerInputData::Motion,
-        });
-    }
-    for entity in drag_start_entities.iter().copied() {
-        // state.dragging_from = drag_start_entities.clone();
-        events.send(PointerInput {
-            entity,
-            // TODO: do not forget about calculating screen/windown/viewport/relative position
-            position: PointerInputPosition { abs: pos, rel: pos },
-            // delta,
-            data: PointerInputData::DragStart,
-        });
-    }
-    if drag_stop_entities.is_empty() {
-        for entity in drag_entities.iter().copied() {
-            events.send(PointerInput {
-                entity,
-                // TODO: do not forget about calculating screen/windown/viewport/relative position
-                position: PointerInputPosition { abs: pos, rel: pos },
-                // delta,
-                data: PointerInputData::Drag,
-            });
-        }
+    for (pointer, entity) in capture.take_lost() {
+        send(pointer, None, vec![(entity, PointerInputData::LostCapture, None)]);
     }
 
-    for entity in drag_stop_entities.iter().copied() {
-        events.send(PointerInput {
-            entity,
-            // TODO: do not forget about calculating screen/windown/viewport/relative position
-            position: PointerInputPosition { abs: pos, rel: pos },
-            // delta,
-            // entities: drag_stop_entities,
-            data: PointerInputData::DragStop,
-        });
+    let (lines, pixels) = read_wheel(&mut wheel, &keys);
+    if lines == Vec2::ZERO && pixels == Vec2::ZERO {
+        return;
     }
-    for entity in up_entities.iter().copied() {
-        events.send(PointerInput {
-            entity,
-            // TODO: do not forget about calculating screen/windown/viewport/relative position
-            position: PointerInputPosition { abs: pos, rel: pos },
-            // delta,
-            // entities: up_entities,
-            data: PointerInputData::Up,
+    let Some((location, entity)) = mouse_hit else {
+        return;
+    };
+    let node = pointer_query
+        .get(entity)
+        .ok()
+        .and_then(|n| n.node.zip(n.global_transform));
+    let scrollable = std::iter::once(entity)
+        .chain(parents.iter_ancestors(entity))
+        .find(|e| {
+            pointer_query
+                .get(*e)
+                .ok()
+                .and_then(|n| n.scrollable)
+                .map_or(false, |s| s.accepts(lines + pixels))
         });
-    }
+    scroll_events.send(ScrollInput {
+        entity,
+        pointer: PointerId::Mouse,
+        position: location.position(node),
+        lines,
+        pixels,
+        scrollable,
+    });
+}
 
-    if up {
-        state.pressed_entities.clear();
-        state.dragging_from.clear();
-        state.press_position = None;
-        state.dragging = false;
+/// Applies the filters to the hits of the backends: the elements without the
+/// `PointerFilter` (or `Scrollable`) are skipped, `Block` hides the elements below.
+fn pick(hits: Vec<PointerHit>, pointer_query: &Query<PointerQuery>) -> Vec<Entity> {
+    let mut picked = vec![];
+    let mut iter = pointer_query.iter_many(hits.iter().map(|hit| hit.entity));
+    while let Some(node) = iter.fetch_next() {
+        // scrollables without the filter pass the pointer through
+        let filter = match (node.filter, node.scrollable) {
+            (Some(filter), _) => filter,
+            (None, Some(_)) => &ActivePointerFilter::Pass,
+            (None, None) => continue,
+        };
+        if !picked.contains(&node.entity) {
+            picked.push(node.entity);
+        }
+        match filter {
+            ActivePointerFilter::Block => {
+                break;
+            }
+            ActivePointerFilter::Pass => { /* allow the next node to be processed */ }
+        }
     }
+    picked
 }
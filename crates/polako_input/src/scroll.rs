@@ -0,0 +1,134 @@
+use bevy::{
+    input::mouse::{MouseScrollUnit, MouseWheel},
+    prelude::*,
+};
+use polako_constructivism::bridge::ReadOnly;
+use polako_constructivism::derive_construct;
+use polako_constructivism::{Get, Singleton};
+
+use crate::{PointerId, PointerInputPosition};
+
+/// Marks the element scrolled by the wheel. The element is hit-tested
+/// even without the `PointerFilter`, so the wheel over its empty space
+/// is delivered to it.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Scrollable {
+    pub horizontal: bool,
+    pub vertical: bool,
+}
+
+impl Default for Scrollable {
+    fn default() -> Self {
+        Scrollable {
+            horizontal: true,
+            vertical: true,
+        }
+    }
+}
+
+impl Scrollable {
+    pub fn horizontal() -> Self {
+        Scrollable {
+            horizontal: true,
+            vertical: false,
+        }
+    }
+
+    pub fn vertical() -> Self {
+        Scrollable {
+            horizontal: false,
+            vertical: true,
+        }
+    }
+
+    /// True if the element scrolls along any axis of the `delta`.
+    pub fn accepts(&self, delta: Vec2) -> bool {
+        (self.horizontal && delta.x != 0.) || (self.vertical && delta.y != 0.)
+    }
+}
+
+/// The mouse wheel rotated over the element, sent to the topmost hit element.
+/// The deltas are the `MouseWheel` deltas summed over the frame.
+#[derive(Event)]
+pub struct ScrollInput {
+    pub entity: Entity,
+    pub pointer: PointerId,
+    pub position: PointerInputPosition,
+    /// The delta of the notched wheels.
+    pub lines: Vec2,
+    /// The delta of the touchpads and the smooth wheels.
+    pub pixels: Vec2,
+    /// The nearest `Scrollable` ancestor (or the element itself)
+    /// accepting the delta.
+    pub scrollable: Option<Entity>,
+}
+
+impl ScrollInput {
+    pub fn position(&self) -> PointerInputPosition {
+        self.position
+    }
+    pub fn lines(&self) -> Vec2 {
+        self.lines
+    }
+    pub fn pixels(&self) -> Vec2 {
+        self.pixels
+    }
+    pub fn scrollable(&self) -> Option<Entity> {
+        self.scrollable
+    }
+}
+
+impl ReadOnly for ScrollInput {}
+
+derive_construct! {
+    seq => ScrollInput -> Nothing;
+    construct => (
+        entity: Entity,
+        pointer: PointerId,
+        position: PointerInputPosition,
+        lines: Vec2,
+        pixels: Vec2,
+        scrollable: Option<Entity>
+    ) -> {
+        ScrollInput { entity, pointer, position, lines, pixels, scrollable }
+    };
+    props => {
+        position: PointerInputPosition = [position, readonly];
+        lines: Vec2 = [lines, readonly];
+        pixels: Vec2 = [pixels, readonly];
+        scrollable: Option<Entity> = [scrollable, readonly];
+    };
+}
+impl ScrollInput {
+    pub fn getters(&self) -> &'static scrollinput_construct::Props<Get> {
+        scrollinput_construct::Props::instance()
+    }
+}
+
+/// Sums the wheel events of the frame into `(lines, pixels)`,
+/// the vertical wheel with Shift pressed scrolls horizontally.
+pub(crate) fn read_wheel(
+    wheel: &mut EventReader<MouseWheel>,
+    keys: &Input<KeyCode>,
+) -> (Vec2, Vec2) {
+    let mut lines = Vec2::ZERO;
+    let mut pixels = Vec2::ZERO;
+    for event in wheel.read() {
+        match event.unit {
+            MouseScrollUnit::Line => lines += Vec2::new(event.x, event.y),
+            MouseScrollUnit::Pixel => pixels += Vec2::new(event.x, event.y),
+        }
+    }
+    if keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        let horizontal = |delta: Vec2| {
+            if delta.x == 0. {
+                Vec2::new(delta.y, 0.)
+            } else {
+                delta
+            }
+        };
+        lines = horizontal(lines);
+        pixels = horizontal(pixels);
+    }
+    (lines, pixels)
+}
@@ -0,0 +1,996 @@
+use bevy::{
+    asset::AssetEvent,
+    ecs::{
+        event::ManualEventReader,
+        system::{RunSystemOnce, SystemId, SystemParamItem},
+    },
+    input::{
+        keyboard::KeyboardInput,
+        mouse::{MouseScrollUnit, MouseWheel},
+        touch::{touch_screen_input_system, TouchPhase},
+        ButtonState,
+    },
+    render::{
+        camera::{camera_system, ManualTextureViews},
+        view::RenderLayers,
+    },
+    window::{ReceivedCharacter, WindowCreated, WindowResized},
+};
+
+use super::*;
+
+use PointerInputData::*;
+
+const LEFT: MouseButton = MouseButton::Left;
+
+fn frame(now: f32, position: Vec2, hits: &[Entity]) -> PointerFrame {
+    PointerFrame {
+        cursor_position: Some(position),
+        hits: hits.to_vec(),
+        hovered: hits.to_vec(),
+        pressed: vec![],
+        released: vec![],
+        delta_seconds: 0.1,
+        now,
+    }
+}
+
+fn press(now: f32, position: Vec2, hits: &[Entity]) -> PointerFrame {
+    PointerFrame {
+        pressed: vec![LEFT],
+        ..frame(now, position, hits)
+    }
+}
+
+fn release(now: f32, position: Vec2, hits: &[Entity]) -> PointerFrame {
+    PointerFrame {
+        released: vec![LEFT],
+        ..frame(now, position, hits)
+    }
+}
+
+fn data(events: &PointerEvents, entity: Entity) -> Vec<PointerInputData> {
+    events
+        .iter()
+        .filter(|(e, _, _)| *e == entity)
+        .map(|(_, data, _)| *data)
+        .collect()
+}
+
+#[test]
+fn test_click() {
+    let settings = PointerSettings::default();
+    let mut state = PointerState::default();
+    let a = Entity::from_raw(1);
+    state.update(frame(0., Vec2::ZERO, &[a]), &settings);
+    let events = state.update(press(0.1, Vec2::ZERO, &[a]), &settings);
+    assert_eq!(vec![Down], data(&events, a));
+    let events = state.update(release(0.2, Vec2::ZERO, &[a]), &settings);
+    assert_eq!(vec![Up, Click], data(&events, a));
+}
+
+#[test]
+fn test_double_click() {
+    let settings = PointerSettings::default();
+    let mut state = PointerState::default();
+    // `b` passes the pointer to `a`
+    let a = Entity::from_raw(1);
+    let b = Entity::from_raw(2);
+    state.update(frame(0., Vec2::ZERO, &[b, a]), &settings);
+    state.update(press(0.1, Vec2::ZERO, &[b, a]), &settings);
+    state.update(release(0.2, Vec2::ZERO, &[b, a]), &settings);
+    state.update(press(0.3, Vec2::ZERO, &[b, a]), &settings);
+    let events = state.update(release(0.4, Vec2::ZERO, &[b, a]), &settings);
+    assert_eq!(vec![Up, Click, DoubleClick], data(&events, a));
+    assert_eq!(vec![Up, Click, DoubleClick], data(&events, b));
+
+    // the third click starts the new double click
+    state.update(press(0.5, Vec2::ZERO, &[b, a]), &settings);
+    let events = state.update(release(0.6, Vec2::ZERO, &[b, a]), &settings);
+    assert_eq!(vec![Up, Click], data(&events, a));
+}
+
+#[test]
+fn test_long_press() {
+    let settings = PointerSettings::default();
+    let mut state = PointerState::default();
+    let a = Entity::from_raw(1);
+    state.update(press(0., Vec2::ZERO, &[a]), &settings);
+    // the time alone doesn't start the drag while the long press is pending
+    let mut now = 0.;
+    let mut events = vec![];
+    while now < 0.65 {
+        now += 0.1;
+        events.extend(state.update(frame(now, Vec2::ZERO, &[a]), &settings));
+    }
+    assert!(!data(&events, a).contains(&DragStart));
+    let events = state.update(frame(0.9, Vec2::ZERO, &[a]), &settings);
+    assert_eq!(vec![LongPress], data(&events, a));
+    let events = state.update(release(1., Vec2::ZERO, &[a]), &settings);
+    assert_eq!(vec![Up], data(&events, a));
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_hover() {
+    let settings = PointerSettings::default();
+    let mut state = PointerState::default();
+    let root = Entity::from_raw(1);
+    let a = Entity::from_raw(2);
+    let events = state.update(
+        PointerFrame {
+            hovered: vec![a, root],
+            ..frame(0., Vec2::ZERO, &[a])
+        },
+        &settings,
+    );
+    let order = events.iter().map(|(e, d, _)| (*e, *d)).collect::<Vec<_>>();
+    // the deprecated `Hover` follows every `Enter`
+    assert_eq!(
+        vec![(a, Over), (root, Enter), (root, Hover), (a, Enter), (a, Hover)],
+        order
+    );
+    let events = state.update(
+        PointerFrame {
+            hovered: vec![root],
+            ..frame(0.1, Vec2::ONE, &[root])
+        },
+        &settings,
+    );
+    let order = events.iter().map(|(e, d, _)| (*e, *d)).collect::<Vec<_>>();
+    assert_eq!(vec![(a, Out), (a, Leave), (root, Over), (root, Motion)], order);
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_drag() {
+    let settings = PointerSettings::default();
+    let mut state = PointerState::default();
+    let a = Entity::from_raw(1);
+    let b = Entity::from_raw(2);
+    state.update(press(0., Vec2::ZERO, &[a]), &settings);
+    let events = state.update(frame(0.1, Vec2::new(30., 0.), &[b]), &settings);
+    assert_eq!(vec![Out, Leave, DragStart], data(&events, a));
+    assert_eq!(vec![Over, Enter, Hover, Drag], data(&events, b));
+    // back to the press position: neither the long press nor the click
+    let events = state.update(frame(2., Vec2::ZERO, &[a]), &settings);
+    assert_eq!(vec![Over, Enter, Hover, Drag], data(&events, a));
+    let events = state.update(release(2.1, Vec2::ZERO, &[a]), &settings);
+    assert_eq!(vec![DragStop, Up], data(&events, a));
+}
+
+#[derive(Component)]
+struct Circle {
+    center: Vec2,
+    radius: f32,
+    depth: f32,
+}
+
+/// Hits the circles in the world depths.
+struct CircleBackend;
+impl PointerBackend for CircleBackend {
+    type Param = Query<'static, 'static, (Entity, &'static Circle)>;
+    fn hit_test(
+        location: &PointerLocation,
+        circles: &SystemParamItem<Self::Param>,
+    ) -> Vec<PointerHit> {
+        circles
+            .iter()
+            .filter(|(_, c)| c.center.distance(location.viewport) <= c.radius)
+            .map(|(entity, c)| PointerHit {
+                entity,
+                depth: c.depth,
+            })
+            .collect()
+    }
+}
+
+#[derive(Component)]
+struct Overlay;
+
+/// Hits every overlay in the ui band.
+struct OverlayBackend;
+impl PointerBackend for OverlayBackend {
+    type Param = Query<'static, 'static, Entity, With<Overlay>>;
+    fn hit_test(
+        _: &PointerLocation,
+        overlays: &SystemParamItem<Self::Param>,
+    ) -> Vec<PointerHit> {
+        overlays
+            .iter()
+            .map(|entity| PointerHit {
+                entity,
+                depth: UI_DEPTH,
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn test_backend_hits_are_merged_by_depth() {
+    let mut world = World::new();
+    let circle = |x: f32, depth: f32| Circle {
+        center: Vec2::new(x, 0.),
+        radius: 10.,
+        depth,
+    };
+    let below = world.spawn(circle(0., 1.)).id();
+    let above = world.spawn(circle(5., 2.)).id();
+    world.spawn(circle(100., 3.));
+    let overlay = world.spawn(Overlay).id();
+    let mut hits = PointerHits::default();
+    let location = PointerLocation {
+        window: None,
+        camera: None,
+        abs: Vec2::ZERO,
+        viewport: Vec2::ZERO,
+    };
+    hits.reset([(PointerId::Mouse, location)].into_iter().collect());
+    world.insert_resource(hits);
+    world.run_system_once(pointer_backend_system::<CircleBackend>);
+    world.run_system_once(pointer_backend_system::<OverlayBackend>);
+    let picked = world
+        .resource_mut::<PointerHits>()
+        .take(PointerId::Mouse)
+        .into_iter()
+        .map(|hit| hit.entity)
+        .collect::<Vec<_>>();
+    assert_eq!(vec![overlay, above, below], picked);
+}
+
+#[test]
+fn test_pointer_shapes() {
+    let size = Vec2::new(100., 50.);
+    let ellipse = PointerShape::ellipse();
+    assert!(ellipse.contains(Vec2::new(50., 25.), size));
+    assert!(!ellipse.contains(Vec2::new(2., 2.), size));
+    let rounded = PointerShape::rounded_rect(10.);
+    assert!(rounded.contains(Vec2::new(50., 1.), size));
+    assert!(!rounded.contains(Vec2::new(1., 1.), size));
+    let triangle =
+        PointerShape::polygon(vec![Vec2::ZERO, Vec2::new(100., 0.), Vec2::new(0., 50.)]);
+    assert!(triangle.contains(Vec2::new(10., 10.), size));
+    assert!(!triangle.contains(Vec2::new(90., 40.), size));
+}
+
+fn tab(world: &mut World, backward: bool) -> Option<Entity> {
+    let mut keys = Input::<KeyCode>::default();
+    keys.press(KeyCode::Tab);
+    if backward {
+        keys.press(KeyCode::ShiftLeft);
+    }
+    world.insert_resource(keys);
+    world.run_system_once(tab_navigation_system);
+    world.resource::<Focused>().0
+}
+
+#[test]
+fn test_tab_navigation() {
+    let mut world = World::new();
+    world.init_resource::<Focused>();
+    let first = world.spawn(Focusable::new(0)).id();
+    let child = world.spawn(Focusable::new(0)).id();
+    let indexed = world.spawn(Focusable::new(2)).id();
+    world.spawn(Focusable::new(-1));
+    world.entity_mut(first).push_children(&[child, indexed]);
+    // the later root without children lives in the former archetype of `first`
+    let last = world.spawn(Focusable::new(0)).id();
+
+    assert_eq!(Some(indexed), tab(&mut world, false));
+    assert_eq!(Some(first), tab(&mut world, false));
+    assert_eq!(Some(child), tab(&mut world, false));
+    assert_eq!(Some(last), tab(&mut world, false));
+    // the pointer-only element is skipped
+    assert_eq!(Some(indexed), tab(&mut world, false));
+    assert_eq!(Some(last), tab(&mut world, true));
+}
+
+#[test]
+fn test_tab_navigation_recycled_ids() {
+    let mut world = World::new();
+    world.init_resource::<Focused>();
+    let despawned = world.spawn_empty().id();
+    world.despawn(despawned);
+    // reuses the index of `despawned` with the next generation,
+    // so the entity of `first` sorts after the entity of `second`
+    let first = world.spawn(Focusable::new(0)).id();
+    let second = world.spawn(Focusable::new(0)).id();
+    assert!(second < first);
+
+    assert_eq!(Some(first), tab(&mut world, false));
+    assert_eq!(Some(second), tab(&mut world, false));
+}
+
+#[test]
+fn test_focusable_passes_pointer_filter() {
+    let mut world = World::new();
+    let focusable = world.spawn(Focusable::default()).id();
+    let blocking = world.spawn((Focusable::default(), PointerFilter::Block)).id();
+    world.run_system_once(focusable_filter_system);
+    assert!(matches!(
+        world.get::<PointerFilter>(focusable),
+        Some(PointerFilter::Pass)
+    ));
+    assert!(matches!(
+        world.get::<PointerFilter>(blocking),
+        Some(PointerFilter::Block)
+    ));
+}
+
+#[test]
+fn test_focus_change() {
+    let mut world = World::new();
+    world.init_resource::<Focused>();
+    world.init_resource::<Events<PointerInput>>();
+    let a = world.spawn(Focusable::default()).id();
+    let b = world.spawn(Focusable::default()).id();
+    // the system remembers the previous focus
+    let system = world.register_system(focus_change_system);
+    let mut reader = world.resource::<Events<PointerInput>>().get_reader();
+    let mut focus = |world: &mut World, entity: Option<Entity>| {
+        world.resource_mut::<Focused>().0 = entity;
+        world.run_system(system).unwrap();
+        reader
+            .read(world.resource::<Events<PointerInput>>())
+            .map(|e| (e.entity, e.data))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(vec![(a, Focus)], focus(&mut world, Some(a)));
+    assert_eq!(vec![(a, Blur), (b, Focus)], focus(&mut world, Some(b)));
+    // the despawned element loses the focus without the blur
+    world.despawn(b);
+    assert!(focus(&mut world, Some(b)).is_empty());
+    assert_eq!(None, world.resource::<Focused>().0);
+}
+
+#[test]
+fn test_key_input_goes_to_focused() {
+    let mut world = World::new();
+    world.init_resource::<Focused>();
+    world.init_resource::<Events<KeyboardInput>>();
+    world.init_resource::<Events<ReceivedCharacter>>();
+    world.init_resource::<Events<KeyInput>>();
+    let input = world.spawn(Focusable::default()).id();
+    let key = |key_code: KeyCode, state: ButtonState| KeyboardInput {
+        scan_code: 0,
+        key_code: Some(key_code),
+        state,
+        window: Entity::PLACEHOLDER,
+    };
+    let char = |char: char| ReceivedCharacter {
+        window: Entity::PLACEHOLDER,
+        char,
+    };
+    let system = world.register_system(key_input_system);
+    let mut reader = world.resource::<Events<KeyInput>>().get_reader();
+
+    // nothing is delivered without the focus
+    world.send_event(key(KeyCode::A, ButtonState::Pressed));
+    world.run_system(system).unwrap();
+    assert_eq!(0, reader.read(world.resource::<Events<KeyInput>>()).count());
+
+    world.resource_mut::<Focused>().0 = Some(input);
+    world.send_event(key(KeyCode::Tab, ButtonState::Pressed));
+    world.send_event(key(KeyCode::B, ButtonState::Pressed));
+    world.send_event(key(KeyCode::B, ButtonState::Released));
+    world.send_event(char('\t'));
+    world.send_event(char('b'));
+    world.run_system(system).unwrap();
+    let delivered = reader
+        .read(world.resource::<Events<KeyInput>>())
+        .map(|e| {
+            assert_eq!(input, e.entity);
+            (e.key(), e.char(), e.key_down())
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            (Some(KeyCode::B), None, true),
+            (Some(KeyCode::B), None, false),
+            (None, Some('b'), false),
+        ],
+        delivered
+    );
+}
+
+#[derive(Component, Clone)]
+struct Card(u32);
+
+fn located(x: f32, y: f32) -> PointerLocation {
+    PointerLocation {
+        window: None,
+        camera: None,
+        abs: Vec2::new(x, y),
+        viewport: Vec2::new(x, y),
+    }
+}
+
+/// The world with the input resources and the systems under test,
+/// `run` executes the systems in order and reads the sent `E` events.
+struct InputWorld<E: Event> {
+    world: World,
+    systems: Vec<SystemId>,
+    reader: ManualEventReader<E>,
+}
+
+impl<E: Event> InputWorld<E> {
+    fn new() -> Self {
+        let mut world = World::new();
+        world.init_resource::<Time>();
+        world.init_resource::<UiScale>();
+        world.init_resource::<Touches>();
+        world.init_resource::<Input<MouseButton>>();
+        world.init_resource::<Input<KeyCode>>();
+        world.init_resource::<PointerSettings>();
+        world.init_resource::<PointerCapture>();
+        world.init_resource::<PointerHits>();
+        world.init_resource::<Events<MouseWheel>>();
+        world.init_resource::<Events<TouchInput>>();
+        world.init_resource::<Events<PointerInput>>();
+        world.init_resource::<Events<ScrollInput>>();
+        world.init_resource::<Events<GestureInput>>();
+        world.init_resource::<Events<E>>();
+        let reader = world.resource::<Events<E>>().get_reader();
+        InputWorld {
+            world,
+            systems: vec![],
+            reader,
+        }
+    }
+
+    fn with_system<M>(mut self, system: impl IntoSystem<(), (), M> + 'static) -> Self {
+        let system = self.world.register_system(system);
+        self.systems.push(system);
+        self
+    }
+
+    fn run(&mut self) -> impl Iterator<Item = &E> + '_ {
+        for system in self.systems.iter() {
+            self.world.run_system(*system).unwrap();
+        }
+        self.reader.read(self.world.resource::<Events<E>>())
+    }
+
+    /// Puts the mouse at `x` over the `hits` (from the topmost).
+    fn hover(&mut self, x: f32, hits: &[Entity]) {
+        let mut pointer_hits = self.world.resource_mut::<PointerHits>();
+        pointer_hits.reset([(PointerId::Mouse, located(x, 0.))].into_iter().collect());
+        pointer_hits.push(
+            PointerId::Mouse,
+            hits.iter().rev().enumerate().map(|(depth, entity)| PointerHit {
+                entity: *entity,
+                depth: depth as f32,
+            }),
+        );
+    }
+}
+
+impl InputWorld<DragDropInput<Card>> {
+    fn drag_drop() -> Self {
+        Self::new().with_system(drag_drop_system::<Card>)
+    }
+
+    /// Runs the frame with the pointer over `picked`, returns the sent inputs.
+    fn frame(
+        &mut self,
+        location: PointerLocation,
+        picked: &[Entity],
+        input: Option<(Entity, PointerInputData)>,
+    ) -> Vec<(Entity, &'static str, bool)> {
+        if let Some((entity, data)) = input {
+            self.world.send_event(PointerInput {
+                entity,
+                pointer: PointerId::Mouse,
+                button: Some(LEFT),
+                position: location.position(None),
+                data,
+            });
+        }
+        let mut hits = self.world.resource_mut::<PointerHits>();
+        hits.reset([(PointerId::Mouse, location)].into_iter().collect());
+        hits.set_picked(PointerId::Mouse, picked.to_vec());
+        self.run()
+            .map(|e| {
+                let data = match e.data {
+                    DragDropData::Enter => "enter",
+                    DragDropData::Over => "over",
+                    DragDropData::Leave => "leave",
+                    DragDropData::Drop => "drop",
+                };
+                (e.entity, data, e.accepted())
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn test_drop_accepted_payload() {
+    let mut drag = InputWorld::drag_drop();
+    let card = drag.world.spawn((Card(7), Draggable::<Card>::new())).id();
+    let small = drag.world.spawn(DropTarget::<Card>::accept(|c| c.0 < 10)).id();
+    let large = drag.world.spawn(DropTarget::<Card>::accept(|c| c.0 >= 10)).id();
+
+    let started = drag.frame(located(0., 0.), &[large], Some((card, DragStart)));
+    assert_eq!(vec![(large, "enter", false)], started);
+    assert_eq!(
+        vec![(large, "over", false)],
+        drag.frame(located(5., 0.), &[large], None)
+    );
+    assert_eq!(
+        vec![(large, "leave", false), (small, "enter", true)],
+        drag.frame(located(50., 0.), &[small], None)
+    );
+    assert_eq!(
+        vec![(small, "drop", true)],
+        drag.frame(located(50., 0.), &[small], Some((card, DragStop)))
+    );
+    // the session is over
+    assert!(drag.frame(located(60., 0.), &[small], None).is_empty());
+}
+
+#[test]
+fn test_rejected_payload_is_not_dropped() {
+    let mut drag = InputWorld::drag_drop();
+    let card = drag.world.spawn((Card(12), Draggable::<Card>::new())).id();
+    let small = drag.world.spawn(DropTarget::<Card>::accept(|c| c.0 < 10)).id();
+    let child = drag.world.spawn_empty().id();
+    drag.world.entity_mut(small).add_child(child);
+
+    // the target is resolved from the ancestors of the picked element
+    let started = drag.frame(located(0., 0.), &[child], Some((card, DragStart)));
+    assert_eq!(vec![(small, "enter", false)], started);
+    assert_eq!(
+        vec![(small, "leave", false)],
+        drag.frame(located(0., 0.), &[child], Some((card, DragStop)))
+    );
+}
+
+#[test]
+fn test_drag_preview_is_placed_in_parent_space() {
+    let mut drag = InputWorld::drag_drop();
+    let panel = drag
+        .world
+        .spawn((
+            Node::default(),
+            GlobalTransform::from_translation(Vec3::new(150., 100., 0.)),
+        ))
+        .id();
+    let preview = drag.world.spawn((Style::default(), Visibility::Hidden)).id();
+    drag.world.entity_mut(panel).add_child(preview);
+    let card = drag
+        .world
+        .spawn((Card(1), Draggable::<Card>::new().with_preview(preview)))
+        .id();
+
+    drag.frame(located(160., 130.), &[], Some((card, DragStart)));
+    let style = drag.world.get::<Style>(preview).unwrap();
+    assert_eq!(PositionType::Absolute, style.position_type);
+    assert_eq!(Val::Px(10.), style.left);
+    assert_eq!(Val::Px(30.), style.top);
+    assert_eq!(
+        Visibility::Inherited,
+        *drag.world.get::<Visibility>(preview).unwrap()
+    );
+
+    drag.frame(located(170., 110.), &[], Some((card, DragStop)));
+    let style = drag.world.get::<Style>(preview).unwrap();
+    assert_eq!(Val::Px(20.), style.left);
+    assert_eq!(Val::Px(10.), style.top);
+    assert_eq!(
+        Visibility::Hidden,
+        *drag.world.get::<Visibility>(preview).unwrap()
+    );
+}
+
+fn located_mouse(world: &mut World) -> Option<PointerLocation> {
+    world.run_system_once(locate_pointers_system);
+    world.resource::<PointerHits>().location(PointerId::Mouse)
+}
+
+#[test]
+fn test_pointer_location() {
+    let mut world = World::new();
+    world.insert_resource(UiScale(2.));
+    world.init_resource::<Touches>();
+    world.init_resource::<PointerHits>();
+    let mut window = Window::default();
+    window.set_cursor_position(Some(Vec2::new(40., 60.)));
+    let window = world.spawn((window, PrimaryWindow)).id();
+    // no ui camera, no location
+    assert!(located_mouse(&mut world).is_none());
+
+    let camera = world.spawn(Camera::default()).id();
+    let location = located_mouse(&mut world).unwrap();
+    assert_eq!(Some(window), location.window);
+    assert_eq!(Some(camera), location.camera);
+    assert_eq!(Vec2::new(40., 60.), location.abs);
+    // the ui scale is applied to the viewport position
+    assert_eq!(Vec2::new(20., 30.), location.viewport);
+
+    world.entity_mut(camera).insert(UiCameraConfig { show_ui: false });
+    assert!(located_mouse(&mut world).is_none());
+}
+
+#[test]
+fn test_pointer_position() {
+    let location = PointerLocation {
+        window: None,
+        camera: None,
+        abs: Vec2::new(60., 80.),
+        viewport: Vec2::new(30., 40.),
+    };
+    // without the node the position is relative to the viewport
+    let position = location.position(None);
+    assert_eq!(Vec2::new(60., 80.), position.abs());
+    assert_eq!(Vec2::new(30., 40.), position.rel());
+    assert_eq!(Vec2::new(30., 40.), position.viewport());
+    assert_eq!(Vec2::ZERO, position.normalized());
+
+    // the node is centered at its transform
+    let node = Node::default();
+    let transform = GlobalTransform::from_translation(Vec3::new(10., 15., 0.));
+    let position = location.position(Some((&node, &transform)));
+    assert_eq!(Vec2::new(20., 25.), position.rel());
+    assert_eq!(Vec2::new(30., 40.), position.viewport());
+    // the empty node doesn't divide by zero
+    assert_eq!(Vec2::ZERO, position.normalized());
+}
+
+fn sprite(world: &mut World, x: f32, layer: u8) -> Entity {
+    world
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    custom_size: Some(Vec2::splat(20.)),
+                    ..default()
+                },
+                global_transform: GlobalTransform::from_xyz(x, 0., 0.),
+                inherited_visibility: InheritedVisibility::VISIBLE,
+                ..default()
+            },
+            RenderLayers::layer(layer),
+        ))
+        .id()
+}
+
+#[test]
+fn test_sprite_backend_cameras() {
+    let mut world = World::new();
+    world.init_resource::<UiScale>();
+    world.init_resource::<Touches>();
+    world.init_resource::<PointerHits>();
+    world.init_resource::<Assets<Image>>();
+    world.init_resource::<ManualTextureViews>();
+    world.init_resource::<Events<WindowCreated>>();
+    world.init_resource::<Events<WindowResized>>();
+    world.init_resource::<Events<AssetEvent<Image>>>();
+    // the cursor is at the center of the 1280x720 window
+    let mut window = Window::default();
+    window.set_cursor_position(Some(Vec2::new(640., 360.)));
+    world.spawn((window, PrimaryWindow));
+    // the world camera is moved to the right and doesn't render ui
+    world.spawn((
+        Camera2dBundle {
+            global_transform: GlobalTransform::from_xyz(100., 0., 999.9),
+            ..default()
+        },
+        UiCameraConfig { show_ui: false },
+    ));
+    // the ui camera renders the sprites of the layer 1 only
+    let ui_camera = world
+        .spawn((
+            Camera2dBundle {
+                camera: Camera {
+                    order: 1,
+                    ..default()
+                },
+                ..default()
+            },
+            RenderLayers::layer(1),
+        ))
+        .id();
+    world.run_system_once(camera_system::<OrthographicProjection>);
+    let world_sprite = sprite(&mut world, 100., 0);
+    let ui_sprite = sprite(&mut world, 0., 1);
+    // under the cursor in the ui camera, but rendered by the world camera only
+    sprite(&mut world, 0., 0);
+
+    let location = located_mouse(&mut world).unwrap();
+    assert_eq!(Some(ui_camera), location.camera);
+    world.run_system_once(pointer_backend_system::<SpriteBackend>);
+    let hits = world
+        .resource_mut::<PointerHits>()
+        .take(PointerId::Mouse)
+        .into_iter()
+        .map(|hit| hit.entity)
+        .collect::<Vec<_>>();
+    assert_eq!(vec![ui_sprite, world_sprite], hits);
+}
+
+impl InputWorld<GestureInput> {
+    fn gestures() -> Self {
+        Self::new()
+            .with_system(touch_screen_input_system)
+            .with_system(gesture_system)
+    }
+
+    fn touch(&mut self, id: u64, phase: TouchPhase, x: f32, y: f32) {
+        self.world.send_event(TouchInput {
+            phase,
+            position: Vec2::new(x, y),
+            force: None,
+            id,
+        });
+    }
+
+    fn down(&mut self, id: u64, entity: Entity) {
+        self.world.send_event(PointerInput {
+            entity,
+            pointer: PointerId::Touch(id),
+            button: Some(LEFT),
+            position: PointerInputPosition::default(),
+            data: Down,
+        });
+    }
+
+    /// Runs the frame `delta` seconds later, returns the recognized gestures.
+    fn frame(&mut self, delta: f32) -> Vec<(Entity, GestureInputData)> {
+        self.world
+            .resource_mut::<Time>()
+            .advance_by(std::time::Duration::from_secs_f32(delta));
+        self.run().map(|e| (e.entity, e.data)).collect()
+    }
+}
+
+#[test]
+fn test_two_finger_gestures() {
+    let mut touch = InputWorld::gestures();
+    let target = Entity::from_raw(1);
+    let other = Entity::from_raw(2);
+    touch.touch(1, TouchPhase::Started, 0., 0.);
+    touch.touch(2, TouchPhase::Started, 100., 0.);
+    touch.down(1, target);
+    touch.down(2, other);
+    assert!(touch.frame(0.1).is_empty());
+
+    // the gesture goes to the element the first touch was pressed on
+    touch.touch(2, TouchPhase::Moved, 200., 0.);
+    let gestures = touch.frame(0.1);
+    assert_eq!(2, gestures.len());
+    assert!(gestures.iter().all(|(entity, _)| *entity == target));
+    assert_eq!(GestureInputData::Pinch { scale: 2. }, gestures[0].1);
+    assert_eq!(
+        GestureInputData::Pan {
+            delta: Vec2::new(50., 0.)
+        },
+        gestures[1].1
+    );
+
+    touch.touch(2, TouchPhase::Moved, 0., 200.);
+    let gestures = touch.frame(0.1);
+    assert_eq!(2, gestures.len());
+    let GestureInputData::Rotate { angle } = gestures[0].1 else {
+        panic!("Expected rotate");
+    };
+    assert!((angle - std::f32::consts::FRAC_PI_2).abs() < 1e-5);
+    assert_eq!(
+        GestureInputData::Pan {
+            delta: Vec2::new(-100., 100.)
+        },
+        gestures[1].1
+    );
+
+    // the touches of the gesture are never swipes
+    touch.touch(1, TouchPhase::Ended, 0., 0.);
+    touch.touch(2, TouchPhase::Ended, 0., 200.);
+    assert!(touch.frame(0.1).is_empty());
+}
+
+#[test]
+fn test_swipe() {
+    let mut touch = InputWorld::gestures();
+    let target = Entity::from_raw(1);
+    touch.touch(1, TouchPhase::Started, 0., 0.);
+    touch.down(1, target);
+    touch.frame(0.1);
+    touch.touch(1, TouchPhase::Moved, 0., 100.);
+    touch.touch(1, TouchPhase::Ended, 0., 100.);
+    let gestures = touch.frame(0.1);
+    assert_eq!(1, gestures.len());
+    assert_eq!(target, gestures[0].0);
+    let GestureInputData::Swipe {
+        direction,
+        velocity,
+    } = gestures[0].1
+    else {
+        panic!("Expected swipe");
+    };
+    assert_eq!(Vec2::Y, direction);
+    assert!((velocity - 1000.).abs() < 1e-2);
+
+    // too slow
+    touch.touch(2, TouchPhase::Started, 0., 0.);
+    touch.down(2, target);
+    touch.frame(0.1);
+    touch.touch(2, TouchPhase::Moved, 100., 0.);
+    touch.touch(2, TouchPhase::Ended, 100., 0.);
+    assert!(touch.frame(1.).is_empty());
+}
+
+impl InputWorld<ScrollInput> {
+    fn scroll() -> Self {
+        Self::new().with_system(pointer_input_system)
+    }
+
+    fn wheel(&mut self, unit: MouseScrollUnit, x: f32, y: f32) {
+        self.world.send_event(MouseWheel {
+            unit,
+            x,
+            y,
+            window: Entity::PLACEHOLDER,
+        });
+    }
+
+    /// Runs the frame with the mouse at `x` over the `hits` (from the topmost),
+    /// returns the sent scroll inputs.
+    fn frame(&mut self, x: f32, hits: &[Entity]) -> Vec<(Entity, Vec2, Vec2, Option<Entity>)> {
+        self.hover(x, hits);
+        self.run()
+            .map(|e| (e.entity, e.lines(), e.pixels(), e.scrollable()))
+            .collect()
+    }
+}
+
+impl InputWorld<PointerInput> {
+    fn pointer() -> Self {
+        Self::new().with_system(pointer_input_system)
+    }
+
+    /// Runs the frame with the mouse at `x` over the `hits` (from the topmost),
+    /// returns the sent pointer inputs.
+    fn frame(&mut self, x: f32, hits: &[Entity]) -> PointerEvents {
+        self.hover(x, hits);
+        let events = self.run().map(|e| (e.entity, e.data, e.button)).collect();
+        self.world.resource_mut::<Input<MouseButton>>().clear();
+        events
+    }
+}
+
+#[test]
+fn test_scroll_goes_to_nearest_scrollable() {
+    let mut input = InputWorld::scroll();
+    let outer = input.world.spawn(Scrollable::horizontal()).id();
+    let list = input.world.spawn(Scrollable::vertical()).id();
+    let item = input.world.spawn(ActivePointerFilter::Pass).id();
+    input.world.entity_mut(outer).add_child(list);
+    input.world.entity_mut(list).add_child(item);
+
+    input.wheel(MouseScrollUnit::Line, 0., -3.);
+    assert_eq!(
+        vec![(item, Vec2::new(0., -3.), Vec2::ZERO, Some(list))],
+        input.frame(0., &[item, list, outer])
+    );
+
+    // the pixels are summed over the frame
+    input.wheel(MouseScrollUnit::Pixel, 0., 5.);
+    input.wheel(MouseScrollUnit::Pixel, 0., 7.);
+    assert_eq!(
+        vec![(item, Vec2::ZERO, Vec2::new(0., 12.), Some(list))],
+        input.frame(0., &[item, list, outer])
+    );
+
+    // the vertical wheel with Shift scrolls horizontally
+    input.world.resource_mut::<Input<KeyCode>>().press(KeyCode::ShiftLeft);
+    input.wheel(MouseScrollUnit::Line, 0., -3.);
+    assert_eq!(
+        vec![(item, Vec2::new(-3., 0.), Vec2::ZERO, Some(outer))],
+        input.frame(0., &[item, list, outer])
+    );
+}
+
+#[test]
+fn test_scroll_over_empty_space() {
+    let mut input = InputWorld::scroll();
+    // the scrollable without the filter is still hit
+    let list = input.world.spawn(Scrollable::vertical()).id();
+    input.wheel(MouseScrollUnit::Line, 0., 1.);
+    assert_eq!(
+        vec![(list, Vec2::new(0., 1.), Vec2::ZERO, Some(list))],
+        input.frame(0., &[list])
+    );
+
+    // nothing accepts the horizontal delta
+    input.wheel(MouseScrollUnit::Line, 1., 0.);
+    assert_eq!(
+        vec![(list, Vec2::new(1., 0.), Vec2::ZERO, None)],
+        input.frame(0., &[list])
+    );
+
+    // no wheel, no scroll
+    assert!(input.frame(0., &[list]).is_empty());
+}
+
+#[test]
+fn test_captured_pointer_stays_on_capturer() {
+    let mut input = InputWorld::pointer();
+    let a = input.world.spawn(ActivePointerFilter::Pass).id();
+    let b = input.world.spawn(ActivePointerFilter::Pass).id();
+
+    input.world.resource_mut::<Input<MouseButton>>().press(LEFT);
+    let events = input.frame(0., &[a]);
+    assert!(data(&events, a).contains(&Down));
+    input
+        .world
+        .resource_mut::<PointerCapture>()
+        .capture_pointer(PointerId::Mouse, a);
+
+    // the pointer leaves the capturer but the events are still sent to it
+    let events = input.frame(10., &[b]);
+    assert!(data(&events, b).is_empty());
+    assert!(!data(&events, a).contains(&Out));
+    assert!(!data(&events, a).contains(&Leave));
+    assert_eq!(
+        &[b],
+        input.world.resource::<PointerHits>().picked(PointerId::Mouse)
+    );
+
+    // the last button up breaks the capture
+    input.world.resource_mut::<Input<MouseButton>>().release(LEFT);
+    let events = input.frame(10., &[b]);
+    assert!(data(&events, b).is_empty());
+    assert!(data(&events, a).contains(&Up));
+    assert_eq!(Some(&LostCapture), data(&events, a).last());
+    assert_eq!(
+        None,
+        input.world.resource::<PointerCapture>().captured(PointerId::Mouse)
+    );
+
+    let events = input.frame(10., &[b]);
+    assert!(data(&events, a).contains(&Out));
+    assert!(data(&events, a).contains(&Leave));
+    assert!(data(&events, b).contains(&Over));
+    assert!(data(&events, b).contains(&Enter));
+}
+
+#[test]
+fn test_capture_is_lost_to_another_element() {
+    let mut input = InputWorld::pointer();
+    let a = input.world.spawn(ActivePointerFilter::Pass).id();
+    let b = input.world.spawn(ActivePointerFilter::Pass).id();
+    let mut capture = input.world.resource_mut::<PointerCapture>();
+    capture.capture_pointer(PointerId::Mouse, a);
+    capture.capture_pointer(PointerId::Mouse, b);
+
+    let events = input.frame(0., &[]);
+    assert_eq!(vec![LostCapture], data(&events, a));
+    assert!(data(&events, b).contains(&Enter));
+
+    // the explicit release doesn't send the `LostCapture`
+    input
+        .world
+        .resource_mut::<PointerCapture>()
+        .release_pointer(PointerId::Mouse);
+    let events = input.frame(0., &[]);
+    assert!(!data(&events, b).contains(&LostCapture));
+    assert!(data(&events, b).contains(&Leave));
+}
+
+#[test]
+fn test_capture_of_despawned_element_is_dropped() {
+    let mut input = InputWorld::pointer();
+    let a = input.world.spawn(ActivePointerFilter::Pass).id();
+    let b = input.world.spawn(ActivePointerFilter::Pass).id();
+    input
+        .world
+        .resource_mut::<PointerCapture>()
+        .capture_pointer(PointerId::Mouse, a);
+    input.world.despawn(a);
+
+    let events = input.frame(0., &[b]);
+    assert!(data(&events, a).is_empty());
+    assert!(data(&events, b).contains(&Enter));
+    assert_eq!(
+        None,
+        input.world.resource::<PointerCapture>().captured(PointerId::Mouse)
+    );
+}
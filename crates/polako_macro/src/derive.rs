@@ -262,11 +262,39 @@ pub struct DeriveSignal {
     ident: Ident,
     construct: DeriveConstruct,
     args: Option<(DeriveConstruct, Vec<Field>)>,
+    bubbles: bool,
+}
+
+/// `#[signal(bubbles)]` or `#[signal(bubbles = false)]`
+pub struct SignalAttrs {
+    bubbles: bool,
+}
+
+impl SignalAttrs {
+    pub fn from_derive(input: &DeriveInput) -> syn::Result<Self> {
+        let mut attrs = SignalAttrs { bubbles: false };
+        for attr in input.attrs.iter().filter(|a| a.path().is_ident("signal")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("bubbles") {
+                    attrs.bubbles = if meta.input.peek(Token![=]) {
+                        meta.value()?.parse::<syn::LitBool>()?.value
+                    } else {
+                        true
+                    };
+                    Ok(())
+                } else {
+                    Err(meta.error("Unknown signal attribute, expected `bubbles`"))
+                }
+            })?;
+        }
+        Ok(attrs)
+    }
 }
 
 impl DeriveSignal {
     pub fn from_derive(input: DeriveInput) -> syn::Result<Self> {
         let ident = input.ident.clone();
+        let bubbles = SignalAttrs::from_derive(&input)?.bubbles;
         let Data::Struct(data) = &input.data else {
             throw!(
                 ident,
@@ -304,6 +332,7 @@ impl DeriveSignal {
             ident,
             args,
             construct,
+            bubbles,
         })
     }
 
@@ -340,12 +369,14 @@ impl DeriveSignal {
             }
         };
         let construct = self.construct.build(&ctx.context)?;
+        let bubbles = self.bubbles;
         Ok(quote! {
             #construct
             impl #flow::Signal for #ident {
                 type Event = Self;
                 type Args = #args_ty;
                 type Descriptor = #descriptor;
+                const BUBBLES: bool = #bubbles;
                 fn filter(event: &Self::Event) -> Option<::bevy::prelude::Entity> {
                     Some(event.entity)
                 }
@@ -365,6 +396,10 @@ impl DeriveSignal {
                     &#descriptor
                 }
             }
+            impl #flow::SignalDescriptor for #descriptor {
+                type Signal = #ident;
+            }
+            impl #flow::HandModifiers for #descriptor {}
             impl #descriptor {
                 pub fn emit(
                     &self,
@@ -387,8 +422,8 @@ impl DeriveSignal {
                     &self,
                     entity: &mut ::bevy::ecs::world::EntityWorldMut<'w>,
                     handler: F,
-                ) {
-                    entity.register_signal_handler::<#ident, S, F>(handler);
+                ) -> #flow::HandId {
+                    entity.register_signal_handler::<#ident, S, F>(handler)
                 }
 
                 pub fn args(&self) -> &'static #cst::Constructor<<#ident as #flow::Signal>::Args> {
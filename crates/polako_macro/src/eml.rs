@@ -75,6 +75,8 @@ impl ParamsExt for Params<Variant> {
 pub enum EmlPathPart {
     /// `hidden` in `.class[hidden]`
     Index(Ident),
+    /// `10` in `.on.pressed.priority[10]`
+    Key(Lit),
     /// `color` in `.bind.color`
     Prop(Ident),
 }
@@ -83,7 +85,11 @@ impl Parse for EmlPathPart {
         if input.peek(Bracket) {
             let content;
             bracketed!(content in input);
-            Ok(EmlPathPart::Index(content.parse()?))
+            if content.peek(Lit) || content.peek(Token![-]) {
+                Ok(EmlPathPart::Key(parse_key(&content)?))
+            } else {
+                Ok(EmlPathPart::Index(content.parse()?))
+            }
         } else {
             Ok(EmlPathPart::Prop(input.parse()?))
         }
@@ -144,6 +150,8 @@ impl Parse for EmlParam {
     }
 }
 
+const HAND_MODIFIERS: &[&str] = &["once", "capture", "priority", "default_action"];
+
 impl EmlParam {
     pub fn build_extension(
         &self,
@@ -151,6 +159,9 @@ impl EmlParam {
         tag: &Ident,
         entity: &TokenStream,
     ) -> syn::Result<TokenStream> {
+        if let Some(props) = self.change_path()? {
+            return self.build_change(ctx, tag, entity, props);
+        }
         let cst = ctx.constructivism();
         let ext_ident = &self.extension;
         let mut ext = quote! {
@@ -165,15 +176,30 @@ impl EmlParam {
                     let ident = ident.to_string();
                     quote! { #ext.at(#ident) }
                 }
+                EmlPathPart::Key(lit) => {
+                    quote! { #ext.at(#lit) }
+                }
             }
         }
         // Ok(quote! { #ext; })
         if let Some(value) = &self.value {
+            // hand modifiers are methods of the `HandModifiers` trait
+            let modifiers = self.path.0.iter().any(|part| match part {
+                EmlPathPart::Prop(ident) => HAND_MODIFIERS.contains(&ident.to_string().as_str()),
+                _ => false,
+            });
+            let modifiers = if modifiers {
+                let flow = ctx.path("flow");
+                quote! { use #flow::HandModifiers as _; }
+            } else {
+                quote! {}
+            };
             let value = Variant::build(value, ctx)?;
             let assign = quote_spanned! { value.span()=>
                 __ext__.assign(#entity, #value)
             };
             Ok(quote! {{
+                #modifiers
                 let __ext__ = #ext;
                 #assign;
             }})
@@ -181,6 +207,58 @@ impl EmlParam {
             Ok(quote! { #ext.declare(#entity); })
         }
     }
+
+    /// Returns `text` for `.on.change.text`. The `.on.change` without the prop
+    /// or with the hand modifier (`.on.change.once`) is the element's `change` signal.
+    fn change_path(&self) -> syn::Result<Option<Vec<Ident>>> {
+        if &self.extension.to_string() != "on" {
+            return Ok(None);
+        }
+        let mut parts = self.path.0.iter();
+        match parts.next() {
+            Some(EmlPathPart::Prop(ident)) if &ident.to_string() == "change" => {}
+            _ => return Ok(None),
+        }
+        let mut props = vec![];
+        for part in parts {
+            match part {
+                EmlPathPart::Prop(ident) => props.push(ident.clone()),
+                EmlPathPart::Index(ident) => throw!(ident, "Expected prop name"),
+                EmlPathPart::Key(lit) => throw!(lit, "Expected prop name"),
+            }
+        }
+        let modifier = |p: &Ident| ["once", "capture"].contains(&p.to_string().as_str());
+        if props.is_empty() || props.iter().all(modifier) {
+            return Ok(None);
+        }
+        Ok(Some(props))
+    }
+
+    fn build_change(
+        &self,
+        ctx: Ref<EmlContext>,
+        tag: &Ident,
+        entity: &TokenStream,
+        props: Vec<Ident>,
+    ) -> syn::Result<TokenStream> {
+        let flow = ctx.path("flow");
+        let Some(value) = &self.value else {
+            throw!(self.extension, "Expected hand: .on.change.prop: (e) => {{ }}");
+        };
+        let prop = Prop {
+            root: parse_quote!(#tag),
+            path: props,
+        }
+        .build(&ctx.context)?;
+        let value = Variant::build(value, ctx)?;
+        let assign = quote_spanned! { value.span()=>
+            __ext__.assign(#entity, #value)
+        };
+        Ok(quote! {{
+            let __ext__ = #flow::PropChange::new(#prop);
+            #assign;
+        }})
+    }
 }
 
 pub struct EmlParams {
@@ -248,35 +326,183 @@ impl EmlParams {
 
 pub struct BindPath {
     path: Vec<Ident>,
+    /// `ScoreChanged` in `events<ScoreChanged>.last.value`
+    events: Option<syn::Type>,
+    /// `[3].name` in `list.items[3].name`
+    tail: Vec<TailSegment>,
     map: Option<BindMap>,
 }
 
 impl Parse for BindPath {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
         let mut path = vec![];
+        let mut tail = vec![];
         let mut map = None;
+        let mut events = None;
         loop {
             if let Some(prop_map) = input.parse_prop_map(&mut path)? {
                 map = Some(prop_map);
                 break;
             }
+            if path.is_empty() && input.peek(syn::Ident) && input.peek2(Token![<]) {
+                let ident = input.parse::<Ident>()?;
+                if &ident.to_string() != "events" {
+                    throw!(ident, "Expected events<Event>.last");
+                }
+                input.parse::<Token![<]>()?;
+                events = Some(input.parse()?);
+                input.parse::<Token![>]>()?;
+                path.push(ident);
+                input.parse::<Token![.]>()?;
+                continue;
+            }
             path.push(input.parse()?);
+            if input.peek(Bracket) {
+                tail = input.parse_tail()?;
+                if !(input.is_empty() || input.peek_bind_direction() || input.peek(Token![,])) {
+                    throw!(input, "Expected bind direction or end of the indexed path");
+                }
+                break;
+            }
             let dot = if input.peek(Token![.]) {
                 Some(input.parse::<Token![.]>()?)
             } else {
                 None
             };
-            if input.is_empty() || input.peek_bind_direction() {
+            if input.is_empty() || input.peek_bind_direction() || input.peek(Token![,]) {
                 if let Some(dot) = dot {
                     path.push(format_ident!("DOT_AUTOCOMPLETE_TOKEN", span = dot.span()));
                 }
                 break;
             }
         }
-        Ok(BindPath { path, map })
+        Ok(BindPath {
+            path,
+            events,
+            tail,
+            map,
+        })
     }
 }
 
+impl BindPath {
+    /// `bg.r` for `content.bg.r`
+    pub fn prop_name(&self) -> String {
+        let mut name = self
+            .path
+            .iter()
+            .skip(1)
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join(".");
+        for segment in self.tail.iter() {
+            name.push_str(&segment.to_string());
+        }
+        name
+    }
+
+    /// Wraps the prop into `Reader::Partial` when the path is indexed.
+    fn build_reader(
+        &self,
+        ctx: &EmlContext,
+        ty: &TokenStream,
+        prop: TokenStream,
+    ) -> TokenStream {
+        if self.tail.is_empty() {
+            return prop;
+        }
+        let flow = ctx.path("flow");
+        let tail = build_tail(&flow, &self.tail, quote! { return None; });
+        quote! {
+            #flow::Reader::Partial(|__host__: &#ty| {
+                let __prop__ = #prop;
+                let __value__ = __prop__.get(__host__);
+                let __item__ = __value__.as_ref();
+                #tail
+                Some(::std::clone::Clone::clone(__item__))
+            })
+        }
+    }
+
+    /// Wraps the prop into `Writer::Partial` when the path is indexed:
+    /// the whole collection is read, the item is replaced and the
+    /// collection is written back.
+    fn build_writer(
+        &self,
+        ctx: &EmlContext,
+        ty: &TokenStream,
+        prop: TokenStream,
+    ) -> TokenStream {
+        if self.tail.is_empty() {
+            return prop;
+        }
+        let flow = ctx.path("flow");
+        let tail = build_tail(&flow, &self.tail, quote! { return None; });
+        let tail_mut =
+            build_tail_write(&flow, &self.tail, quote! { __value__ }, quote! { return false; });
+        quote! {
+            #flow::Writer::Partial {
+                get: |__host__: &#ty| {
+                    let __prop__ = #prop;
+                    let __value__ = __prop__.get(__host__);
+                    let __item__ = __value__.as_ref();
+                    #tail
+                    Some(::std::clone::Clone::clone(__item__))
+                },
+                set: |__host__: &mut #ty, __value__| {
+                    let __prop__ = #prop;
+                    let mut __collection__ = __prop__.get(__host__).get();
+                    {
+                        let __item__ = &mut __collection__;
+                        #tail_mut
+                    }
+                    (__prop__.setter())(__host__, __collection__);
+                    true
+                },
+            }
+        }
+    }
+}
+
+/// `EventsReader` for `events<E>.last.prop` or `mark.last.prop`
+fn build_events_reader(
+    ctx: &EmlContext,
+    from: &BindPath,
+    events: &syn::Type,
+) -> syn::Result<TokenStream> {
+    let flow = ctx.path("flow");
+    let mark = &from.path[0];
+    let mut path = from.path.iter().skip(1).cloned().collect::<Vec<_>>();
+    if path.is_empty() || &path.remove(0).to_string() != "last" {
+        throw!(mark, "Expected {}.last or {}.last.prop", mark, mark);
+    }
+    let from_name = from.prop_name();
+    if path.is_empty() {
+        if !from.tail.is_empty() || from.map.is_some() {
+            throw!(mark, "Expected {}.last.prop", mark);
+        }
+        return Ok(quote! {
+            #flow::EventsReader::<#events, #events>::last_event().named(#from_name)
+        });
+    }
+    if let (Some(map), false) = (&from.map, from.tail.is_empty()) {
+        throw!(map, "Indexed bind source can't be mapped.");
+    }
+    let prop = Prop {
+        root: parse_quote!(#events),
+        path,
+    }
+    .build(&ctx.context)?;
+    let prop = from.build_reader(ctx, &quote! { #events }, prop);
+    let prop = if let Some(map) = &from.map {
+        let map = map.build(ctx)?;
+        quote! { #prop.map(#map) }
+    } else {
+        prop
+    };
+    Ok(quote! { #flow::EventsReader::last(#prop).named(#from_name) })
+}
+
 pub enum BindDirection {
     Forward,
     Backward,
@@ -306,6 +532,53 @@ pub struct Bind {
     to: BindPath,
     #[allow(unused)]
     bidirectional: bool,
+    compare: Option<BindCompare>,
+    /// `priority(10)` in `bind(a.value => b.value, priority(10))`
+    priority: Option<syn::Expr>,
+}
+
+/// `epsilon(0.01)` in `bind(a.value => b.value, epsilon(0.01))`
+pub enum BindCompare {
+    Always,
+    Eq,
+    Epsilon(syn::Expr),
+    Custom(syn::Expr),
+}
+
+impl Parse for BindCompare {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let ident = input.parse::<Ident>()?;
+        Ok(match ident.to_string().as_str() {
+            "always" => BindCompare::Always,
+            "eq" => BindCompare::Eq,
+            "epsilon" => {
+                let content;
+                parenthesized!(content in input);
+                BindCompare::Epsilon(content.parse()?)
+            }
+            "compare" => {
+                let content;
+                parenthesized!(content in input);
+                BindCompare::Custom(content.parse()?)
+            }
+            _ => throw!(
+                ident,
+                "Expected always, eq, epsilon(value) or compare(func)"
+            ),
+        })
+    }
+}
+
+impl BindCompare {
+    pub fn build(&self, ctx: &EmlContext) -> TokenStream {
+        let flow = ctx.path("flow");
+        match self {
+            BindCompare::Always => quote! { #flow::Comparator::Always },
+            BindCompare::Eq => quote! { #flow::Comparator::eq() },
+            BindCompare::Epsilon(epsilon) => quote! { #flow::Comparator::epsilon(#epsilon) },
+            BindCompare::Custom(func) => quote! { #flow::Comparator::Custom(#func) },
+        }
+    }
 }
 
 impl Parse for Bind {
@@ -313,26 +586,50 @@ impl Parse for Bind {
         let first: BindPath = input.parse()?;
         if let Ok(direction) = input.parse::<BindDirection>() {
             let second: BindPath = input.parse()?;
+            let mut compare = None;
+            let mut priority = None;
+            while input.peek(Token![,]) {
+                input.parse::<Token![,]>()?;
+                if input.peek(syn::Ident) && input.peek2(token::Paren) {
+                    let fork = input.fork();
+                    if &fork.parse::<Ident>()?.to_string() == "priority" {
+                        input.parse::<Ident>()?;
+                        let content;
+                        parenthesized!(content in input);
+                        priority = Some(content.parse()?);
+                        continue;
+                    }
+                }
+                compare = Some(input.parse()?);
+            }
             Ok(match direction {
                 BindDirection::Forward => Bind {
                     from: first,
                     to: second,
                     bidirectional: false,
+                    compare,
+                    priority,
                 },
                 BindDirection::Backward => Bind {
                     from: second,
                     to: first,
                     bidirectional: true,
+                    compare,
+                    priority,
                 },
                 BindDirection::Both => Bind {
                     from: first,
                     to: second,
                     bidirectional: true,
+                    compare,
+                    priority,
                 },
             })
         } else {
             let second = BindPath {
                 map: None,
+                events: None,
+                tail: vec![],
                 path: vec![
                     format_ident!("DOT_AUTOCOMPLETE_TOKEN"),
                     format_ident!("DOT_AUTOCOMPLETE_TOKEN"),
@@ -342,6 +639,8 @@ impl Parse for Bind {
                 from: first,
                 to: second,
                 bidirectional: false,
+                compare: None,
+                priority: None,
             })
         }
     }
@@ -349,7 +648,12 @@ impl Parse for Bind {
 
 pub enum EmlDirective {
     Resource(Ident, Ident),
+    /// `events<ScoreChanged>(score)`, bind it as `score.last.value`
+    Events(Ident, syn::Type),
     Bind(Bind),
+    /// `time.on.change.elapsed: (e) => { }`, the trailing `.always` emits
+    /// on every change of the resource without comparing the values.
+    Change(Ident, Vec<Ident>, Variant),
     None,
 }
 
@@ -373,8 +677,54 @@ impl EmlDirective {
     }
 }
 
+impl EmlDirective {
+    fn parse_change(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mark = input.parse::<Ident>()?;
+        for expected in ["on", "change"] {
+            input.parse::<Token![.]>()?;
+            let ident = input.parse::<Ident>()?;
+            if &ident.to_string() != expected {
+                throw!(ident, "Expected {}: {}.on.change: (e) => {{ }}", expected, mark);
+            }
+        }
+        let mut path = vec![];
+        while input.peek(Token![.]) {
+            input.parse::<Token![.]>()?;
+            path.push(input.parse()?);
+        }
+        input.parse::<Token![:]>()?;
+        let value = input.parse::<Variant>()?;
+        if !matches!(value, Variant::Hand(_)) {
+            throw!(mark, "Expected hand: {}.on.change: (e) => {{ }}", mark);
+        }
+        if input.peek(Token![;]) {
+            input.parse::<Token![;]>()?;
+        }
+        Ok(EmlDirective::Change(mark, path, value))
+    }
+}
+
 impl Parse for EmlDirective {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        if input.peek(syn::Ident) && input.peek2(Token![.]) {
+            return Self::parse_change(input);
+        }
+        if input.peek(syn::Ident) && input.peek2(Token![<]) {
+            let ident = input.parse::<Ident>()?;
+            if &ident.to_string() != "events" {
+                throw!(ident, "Unknown directive");
+            }
+            input.parse::<Token![<]>()?;
+            let ty = input.parse()?;
+            input.parse::<Token![>]>()?;
+            let content;
+            parenthesized!(content in input);
+            let mark = content.parse()?;
+            if input.peek(Token![;]) {
+                input.parse::<Token![;]>()?;
+            }
+            return Ok(EmlDirective::Events(mark, ty));
+        }
         if !(input.peek(syn::Ident) && input.peek2(token::Paren)) {
             return Ok(EmlDirective::None);
         }
@@ -642,6 +992,8 @@ impl Parse for EmlRoot {
 pub struct EmlContext {
     pub context: Context,
     pub variables: HashMap<Ident, Mark>,
+    /// Marks declared with `events<Event>(mark)`
+    pub events: HashMap<Ident, syn::Type>,
     strict: bool,
 }
 
@@ -650,6 +1002,7 @@ impl EmlContext {
         Self {
             context: Context::new(prefix),
             variables: HashMap::new(),
+            events: HashMap::new(),
             strict: false,
         }
     }
@@ -948,10 +1301,17 @@ impl Eml {
         let bevy = quote! { ::bevy::prelude };
         let mut body = quote! {};
         let variables = self.fetch_variables()?;
+        let mut events = HashMap::new();
+        for directive in self.directives.iter() {
+            if let EmlDirective::Events(mark, ty) = directive {
+                events.insert(mark.clone(), ty.clone());
+            }
+        }
         let mut root_ty = None;
         build(
             EmlContext {
                 variables,
+                events,
                 context: Context::new("polako"),
                 strict: self.strict,
             },
@@ -974,36 +1334,115 @@ impl Eml {
                             let #ident: #eml::EntityMark<#tag> = #eml::EntityMark::new(#entity);
                         }
                     }
+                    if !self.strict {
+                        let name = ident.to_string();
+                        body = quote! { #body
+                            #eml::EmlMarks::record(world, #ident.entity, #name);
+                        }
+                    }
                 }
                 for directive in self.directives.iter() {
                     let built_directive = directive.build(&ctx)?;
                     body = quote! { #body #built_directive };
-                    let EmlDirective::Bind(bind) = directive else {
+                    if let EmlDirective::Change(mark, path, value) = directive {
+                        let Some(var) = ctx.variables.get(mark) else {
+                            throw!(mark, "Undeclared variable {}", mark.to_string());
+                        };
+                        let flow = ctx.path("flow");
+                        let ty = &var.ty;
+                        let value = Variant::build(value, ctx)?;
+                        body = if var.is_entity() {
+                            if path.is_empty() {
+                                throw!(mark, "Expected prop name: {}.on.change.prop", mark);
+                            }
+                            let prop = Prop {
+                                root: parse_quote!(#ty),
+                                path: path.clone(),
+                            }
+                            .build(&ctx.context)?;
+                            let ident = &var.ident;
+                            quote! { #body
+                                #flow::PropChange::new(#prop)
+                                    .assign(&mut world.entity_mut(#ident.entity), #value);
+                            }
+                        } else {
+                            // `time.on.change.always` watches the values without `PartialEq`
+                            let mut path = path.clone();
+                            let always = path.last().map_or(false, |p| &p.to_string() == "always");
+                            if always {
+                                path.pop();
+                            }
+                            let compare = if always {
+                                quote! { #flow::Comparator::Always }
+                            } else {
+                                quote! { #flow::Comparator::eq() }
+                            };
+                            let change = if path.is_empty() {
+                                quote! { #flow::ResourceChange::<#ty, #ty>::resource_with(#compare) }
+                            } else {
+                                let prop = Prop {
+                                    root: parse_quote!(#ty),
+                                    path,
+                                }
+                                .build(&ctx.context)?;
+                                quote! { #flow::ResourceChange::new_with(#prop, #compare) }
+                            };
+                            quote! { #body
+                                #change.assign(&mut world.entity_mut(__root__), #value);
+                            }
+                        };
                         continue;
-                    };
-                    let mut from_path = bind.from.path.clone();
-                    let from_var = from_path.remove(0);
-                    let Some(from_var) = ctx.variables.get(&from_var) else {
-                        throw!(from_var, "Undeclared variable {}", from_var.to_string());
-                    };
-
-                    let from_ty = &from_var.ty;
-                    let from_prop = Prop {
-                        root: parse_quote!(#from_ty),
-                        path: from_path,
                     }
-                    .build(&ctx.context)?;
-                    let from_prop = if let Some(map) = &bind.from.map {
-                        let map = map.build(&ctx)?;
-                        quote! { #from_prop.map(#map) }
-                    } else {
-                        from_prop
+                    let EmlDirective::Bind(bind) = directive else {
+                        continue;
                     };
-                    let from_bind = if from_var.is_entity() {
-                        let ident = &from_var.ident;
-                        quote! { #ident.entity.get(#from_prop) }
+                    let events = bind
+                        .from
+                        .events
+                        .as_ref()
+                        .or_else(|| ctx.events.get(&bind.from.path[0]));
+                    let (source, from_bind) = if let Some(events) = events {
+                        let from_bind = build_events_reader(&ctx, &bind.from, events)?;
+                        (format_ident!("events"), from_bind)
                     } else {
-                        quote! { #from_prop.into() }
+                        let mut from_path = bind.from.path.clone();
+                        let from_var = from_path.remove(0);
+                        let Some(from_var) = ctx.variables.get(&from_var) else {
+                            throw!(from_var, "Undeclared variable {}", from_var.to_string());
+                        };
+
+                        let from_ty = &from_var.ty;
+                        let from_prop = Prop {
+                            root: parse_quote!(#from_ty),
+                            path: from_path,
+                        }
+                        .build(&ctx.context)?;
+                        if let (Some(map), false) = (&bind.from.map, bind.from.tail.is_empty()) {
+                            throw!(map, "Indexed bind source can't be mapped.");
+                        }
+                        let from_prop =
+                            bind.from
+                                .build_reader(&ctx, &quote! { #from_ty }, from_prop);
+                        let from_prop = if let Some(map) = &bind.from.map {
+                            let map = map.build(&ctx)?;
+                            quote! { #from_prop.map(#map) }
+                        } else {
+                            from_prop
+                        };
+                        let from_name = bind.from.prop_name();
+                        let source = if from_var.is_entity() {
+                            format_ident!("component")
+                        } else {
+                            format_ident!("resource")
+                        };
+                        let from_bind = if from_var.is_entity() {
+                            let ident = &from_var.ident;
+                            quote! { #ident.entity.get(#from_prop).named(#from_name) }
+                        } else {
+                            let flow = ctx.path("flow");
+                            quote! { #flow::ResourceReader::from(#from_prop).named(#from_name) }
+                        };
+                        (source, from_bind)
                     };
 
                     let mut to_path = bind.to.path.clone();
@@ -1017,25 +1456,31 @@ impl Eml {
                         path: to_path,
                     }
                     .build(&ctx.context)?;
+                    let to_prop = bind.to.build_writer(&ctx, &quote! { #to_ty }, to_prop);
                     if let Some(map) = &bind.to.map {
-                        throw!(map, "Bind target prop can't be mapped.");
+                        throw!(map, "Bind target prop can't be mapped, use MapBack from Rust.");
                     }
                     if to_var.is_resource() {
                         throw!(bind.to.path[0], "Resources can't be used as bind targets.");
                     }
+                    let to_name = bind.to.prop_name();
                     let to_bind = {
                         let ident = &to_var.ident;
+                        let priority = bind.priority.as_ref().map(|p| quote! { .priority(#p) });
                         quote! {
-                            #ident.entity.set(#to_prop)
+                            #ident.entity.set(#to_prop).named(#to_name)#priority
                         }
                     };
-                    body = if from_var.is_entity() {
+                    body = if let Some(compare) = &bind.compare {
+                        let compare = compare.build(&ctx);
+                        let method = format_ident!("bind_{}_to_component_with", source);
                         quote! { #body
-                            world.bind_component_to_component(#from_bind, #to_bind);
+                            world.#method(#from_bind, #to_bind, #compare);
                         }
                     } else {
+                        let method = format_ident!("bind_{}_to_component", source);
                         quote! { #body
-                            world.bind_resource_to_component(#from_bind, #to_bind);
+                            world.#method(#from_bind, #to_bind);
                         }
                     };
                 }
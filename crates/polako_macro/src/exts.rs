@@ -1,12 +1,12 @@
 use crate::eml::EmlContext;
 use constructivist::throw;
 use proc_macro2::{Ident, Span, TokenStream};
-use quote::quote;
+use quote::{format_ident, quote};
 use syn::{
-    parenthesized,
+    bracketed, parenthesized,
     parse::{Parse, ParseBuffer},
     spanned::Spanned,
-    token, Expr, Lit, LitStr, Token,
+    token, Expr, Lit, LitInt, LitStr, Token,
 };
 
 pub trait PeekBindDirection {
@@ -159,3 +159,134 @@ impl Parse for BindMap {
         Ok(BindMap::Format(fmt, args))
     }
 }
+
+/// `10` in `[10]`, `"gold"` in `["gold"]`, negative integers are allowed.
+pub fn parse_key(input: syn::parse::ParseStream) -> syn::Result<Lit> {
+    if input.peek(Token![-]) {
+        input.parse::<Token![-]>()?;
+        let lit = input.parse::<LitInt>()?;
+        let negative = format!("-{}", lit.base10_digits());
+        Ok(Lit::Int(LitInt::new(&negative, lit.span())))
+    } else {
+        input.parse()
+    }
+}
+
+/// The part of the path after the first index: `[3].name` in `list.items[3].name`.
+/// Indices are resolved through the `PropIndex`, fields through the
+/// `Props<Get>` and `Props<Set>` of the item type.
+#[derive(Clone)]
+pub enum TailSegment {
+    Index(Lit),
+    Field(Ident),
+}
+
+impl TailSegment {
+    pub fn span(&self) -> Span {
+        match self {
+            TailSegment::Index(lit) => lit.span(),
+            TailSegment::Field(ident) => ident.span(),
+        }
+    }
+}
+
+impl ToString for TailSegment {
+    fn to_string(&self) -> String {
+        match self {
+            TailSegment::Index(Lit::Str(s)) => format!("[{:?}]", s.value()),
+            TailSegment::Index(lit) => format!("[{}]", quote! { #lit }),
+            TailSegment::Field(ident) => format!(".{ident}"),
+        }
+    }
+}
+
+pub trait ParseTail {
+    /// Parses `[3].name["key"]` while the next token is `[` or `.ident`.
+    /// Stops before `.ident(` so the method calls are left to the caller.
+    fn parse_tail(&self) -> syn::Result<Vec<TailSegment>>;
+}
+
+impl<'a> ParseTail for ParseBuffer<'a> {
+    fn parse_tail(&self) -> syn::Result<Vec<TailSegment>> {
+        let mut tail = vec![];
+        loop {
+            if self.peek(token::Bracket) {
+                let content;
+                bracketed!(content in self);
+                tail.push(TailSegment::Index(parse_key(&content)?));
+            } else if !tail.is_empty()
+                && self.peek(Token![.])
+                && self.peek2(syn::Ident)
+                && !self.peek3(token::Paren)
+            {
+                self.parse::<Token![.]>()?;
+                tail.push(TailSegment::Field(self.parse()?));
+            } else {
+                break;
+            }
+        }
+        Ok(tail)
+    }
+}
+
+/// Resolves the tail against `__item__` (the reference to the collection),
+/// `miss` is executed when the index is out of range. Leaves the reference
+/// to the resolved item in `__item__`.
+pub fn build_tail(flow: &TokenStream, tail: &[TailSegment], miss: TokenStream) -> TokenStream {
+    let mut out = quote! {};
+    for segment in tail.iter() {
+        out = match segment {
+            TailSegment::Index(key) => quote! { #out
+                let Some(__item__) = #flow::PropIndex::get_at(__item__, #key) else { #miss };
+            },
+            TailSegment::Field(field) => quote! { #out
+                let __value__ = #flow::getters_of(__item__).#field(__item__).into_value();
+                let __item__ = __value__.as_ref();
+            },
+        }
+    }
+    out
+}
+
+/// Writes `value` to the tail of `__item__` (the mutable reference to the
+/// collection), `miss` is executed when the index is out of range. Fields are
+/// read, updated and written back with the setters.
+pub fn build_tail_write(
+    flow: &TokenStream,
+    tail: &[TailSegment],
+    value: TokenStream,
+    miss: TokenStream,
+) -> TokenStream {
+    let Some((segment, rest)) = tail.split_first() else {
+        return quote! { *__item__ = #value; };
+    };
+    match segment {
+        TailSegment::Index(key) => {
+            let inner = build_tail_write(flow, rest, value, miss.clone());
+            quote! {
+                let Some(__item__) = #flow::PropIndex::get_at_mut(__item__, #key) else { #miss };
+                #inner
+            }
+        }
+        TailSegment::Field(field) => {
+            let setter = format_ident!("set_{}", field);
+            if rest.is_empty() {
+                return quote! {
+                    #flow::setters_of(&*__item__).#setter(__item__, #value);
+                };
+            }
+            let inner = build_tail_write(flow, rest, value, miss);
+            quote! {
+                let mut __field__ = #flow::getters_of(&*__item__)
+                    .#field(&*__item__)
+                    .into_value()
+                    .get();
+                {
+                    let __item__ = &mut __field__;
+                    #inner
+                }
+                #flow::setters_of(&*__item__).#setter(__item__, __field__);
+            }
+        }
+    }
+}
@@ -17,7 +17,10 @@ use syn::{
     Lit, LitStr, Token,
 };
 
-use crate::eml::{EmlContext, Mark, MarkKind};
+use crate::{
+    eml::{EmlContext, Mark, MarkKind},
+    exts::{build_tail, build_tail_write, ParseTail, TailSegment},
+};
 
 /// Samples:
 /// ```ignore
@@ -63,7 +66,14 @@ pub struct HandBuilder {
     ctx: Ref<EmlContext>,
     access: Vec<AccessPoint>,
     reads: HashMap<Path, Option<usize>>,
+    /// Collections written by index: `list.items` for `list.items[3] = ..`
+    collections: HashSet<Path>,
+    /// Indexed reads of the statement being built, the statement is
+    /// skipped if any of the items is missing
+    guards: Vec<Ident>,
     args: HashSet<Ident>,
+    /// The hand controls the propagation and requires the `SignalDispatch`
+    dispatch: bool,
 }
 
 impl ContextLike for HandBuilder {
@@ -85,7 +95,10 @@ impl HandBuilder {
             ctx: eml_context,
             access: vec![],
             reads: HashMap::new(),
+            collections: HashSet::new(),
+            guards: vec![],
             args: locals.into_iter().collect(),
+            dispatch: false,
         }
     }
     pub fn signature(&self) -> syn::Result<TokenStream> {
@@ -124,19 +137,32 @@ impl HandBuilder {
         } else {
             quote! { ::bevy::ecs::system::ParamSet<(#items)> }
         };
+        let dispatch = if self.dispatch {
+            let flow = self.path("flow");
+            quote! { ::bevy::prelude::Res<'static, #flow::SignalDispatch>, }
+        } else {
+            quote! {}
+        };
         Ok(quote! {
             #event: &_,
             _params: &mut ::bevy::ecs::system::StaticSystemParam<(
                 ::bevy::prelude::Commands,
+                #dispatch
                 #params,
             )>
         })
     }
     pub fn header(&self) -> syn::Result<TokenStream> {
         let mut header = quote! {};
+        let flow = self.path("flow");
         for (path, idx) in self.reads.iter() {
             let ident = path.mark();
             let var = path.var();
+            let var = if self.collections.contains(path) {
+                quote! { mut #var }
+            } else {
+                quote! { #var }
+            };
             let mut get = quote! { #ident.getters() };
             for (idx, part) in path.0.iter().skip(1).enumerate() {
                 if idx == 0 {
@@ -145,11 +171,25 @@ impl HandBuilder {
                     get = quote! { #get.#part() };
                 }
             }
+            // the indexed read is `None` if the item is missing
+            let get = if path.1.is_empty() {
+                quote! { #get.into_value().get() }
+            } else {
+                let tail = build_tail(&flow, &path.1, quote! { break '__read__ None; });
+                quote! {
+                    '__read__: {
+                        let __value__ = #get.into_value();
+                        let __item__ = __value__.as_ref();
+                        #tail
+                        Some(::std::clone::Clone::clone(__item__))
+                    }
+                }
+            };
             if let Some(event) = self.args.get(&ident) {
                 header = quote! { #header
                     let #var = {
                         let _host = #event;
-                        #get.into_value().get()
+                        #get
                     };
                 };
             } else if let Some(idx) = idx {
@@ -163,7 +203,7 @@ impl HandBuilder {
                                 let #var = {
                                     let _inset = _params.#param_idx();
                                     let _host = _inset.get(#ident.entity).unwrap();
-                                    #get.into_value().get()
+                                    #get
                                 };
                             }
                         }
@@ -172,7 +212,7 @@ impl HandBuilder {
                                 #header
                                 let #var = {
                                     let _host = _params.#param_idx();
-                                    #get.into_value().get()
+                                    #get
                                 };
                             }
                         }
@@ -186,7 +226,7 @@ impl HandBuilder {
                                     let _inset = _params.#param_idx();
                                     let _mut = _inset.get(#ident.entity).unwrap();
                                     let _host = &_mut;
-                                    #get.into_value().get()
+                                    #get
                                 };
                             }
                         }
@@ -196,7 +236,7 @@ impl HandBuilder {
                                 let #var = {
                                     let _inset = _params.#param_idx();
                                     let _host = &_inset;
-                                    #get.into_value().get()
+                                    #get
                                 };
                             }
                         }
@@ -204,8 +244,13 @@ impl HandBuilder {
                 }
             }
         }
+        let unpack = if self.dispatch {
+            quote! { let (_commands, _dispatch, _params) = ::std::ops::DerefMut::deref_mut(_params); }
+        } else {
+            quote! { let (_commands, _params) = ::std::ops::DerefMut::deref_mut(_params); }
+        };
         Ok(quote! {
-            let (_commands, _params) = ::std::ops::DerefMut::deref_mut(_params);
+            #unpack
             #header
         })
     }
@@ -216,7 +261,29 @@ impl HandBuilder {
         let ident = path.var();
         let idx = self.add_input(&path)?;
         self.reads.insert(path.clone(), idx);
-        Ok(quote! { #ident })
+        if path.1.is_empty() {
+            return Ok(quote! { #ident });
+        }
+        if !self.guards.contains(&ident) {
+            self.guards.push(ident.clone());
+        }
+        let item = format_ident!("{}__", ident);
+        Ok(quote! { #item })
+    }
+
+    /// Runs `stmt` only if the items read by index since the `from`
+    /// guard are present.
+    pub fn guarded(&mut self, from: usize, stmt: TokenStream) -> TokenStream {
+        let mut stmt = stmt;
+        for var in self.guards.drain(from..).rev() {
+            let item = format_ident!("{}__", var);
+            stmt = quote! {
+                if let Some(#item) = ::std::clone::Clone::clone(&#var) {
+                    #stmt
+                }
+            };
+        }
+        stmt
     }
     pub fn write(&mut self, path: &Path, value: TokenStream) -> syn::Result<TokenStream> {
         let ident = path.var();
@@ -234,10 +301,12 @@ impl HandBuilder {
         let notify_change = match mark.kind {
             MarkKind::Entity => {
                 let descriptor = &path.0[1];
+                let prop = path.prop_name();
                 quote! {
-                    #mark_ident.descriptor().#descriptor().notify_changed(
+                    #mark_ident.descriptor().#descriptor().notify_prop_changed(
                         _commands,
-                        #mark_ident.entity
+                        #mark_ident.entity,
+                        #prop
                     )
                 }
             }
@@ -258,12 +327,44 @@ impl HandBuilder {
             }
         }
         self.reads.insert(path.clone(), Some(idx));
+        if path.1.is_empty() {
+            return Ok(quote! {
+                {
+                    let _val = (#value).into();
+                    if #ident != _val {
+                        #set;
+                        #notify_change;
+                    }
+                }
+            });
+        }
+        // `list.items[3] = ..` replaces the item in place of the read
+        // collection and writes the collection back with the prop setter
+        let collection = Path(path.0.clone(), vec![]);
+        let collection_var = collection.var();
+        self.reads.insert(collection.clone(), Some(idx));
+        self.collections.insert(collection);
+        let flow = self.path("flow");
+        let tail = build_tail_write(
+            &flow,
+            &path.1,
+            quote! { _val },
+            quote! { break '__write__; },
+        );
         Ok(quote! {
             {
                 let _val = (#value).into();
-                if #ident != _val {
-                    #set;
-                    #notify_change;
+                // the write is skipped if the item is missing
+                if #ident.as_ref().map_or(false, |_current| *_current != _val) {
+                    '__write__: {
+                        {
+                            let __item__ = &mut #collection_var;
+                            #tail
+                        }
+                        let _val = ::std::clone::Clone::clone(&#collection_var);
+                        #set;
+                        #notify_change;
+                    }
                 }
             }
         })
@@ -287,6 +388,8 @@ impl HandBuilder {
                 self.access.push(point);
                 Some(idx)
             }
+        } else if self.ctx.events.contains_key(&ident) {
+            throw!(ident, "Events can't be used in hands, bind them instead");
         } else {
             throw!(ident, "Undefined mark");
         })
@@ -309,6 +412,8 @@ impl HandBuilder {
                 self.access.push(point);
                 (mark, idx)
             }
+        } else if self.ctx.events.contains_key(&ident) {
+            throw!(ident, "Events can't be used in hands, bind them instead");
         } else {
             throw!(ident, "Undefined mark");
         })
@@ -365,8 +470,9 @@ impl Hand {
     }
 }
 
+/// `list.items[3].name` is `Path([list, items], [[3], .name])`
 #[derive(Clone, Eq)]
-pub struct Path(Vec<Ident>);
+pub struct Path(Vec<Ident>, Vec<TailSegment>);
 
 impl std::ops::Deref for Path {
     type Target = Vec<Ident>;
@@ -389,11 +495,38 @@ impl Path {
         self.0[1].clone()
     }
 
+    /// `items[3].name` for `list.items[3].name`
+    pub fn prop_name(&self) -> String {
+        let mut name = self
+            .0
+            .iter()
+            .skip(1)
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join(".");
+        for segment in self.1.iter() {
+            name.push_str(&segment.to_string());
+        }
+        name
+    }
+
     pub fn var(&self) -> Ident {
         let mut ident = format_ident!("_v_{}", self.0[0]);
         for part in self.0.iter().skip(1) {
             ident = format_ident!("{}_{}", ident, part);
         }
+        for segment in self.1.iter() {
+            let part = segment
+                .to_string()
+                .chars()
+                .map(|c| match c {
+                    c if c.is_ascii_alphanumeric() => c.to_string(),
+                    '.' | '[' | ']' => "_".to_string(),
+                    c => format!("x{:x}", c as u32),
+                })
+                .collect::<String>();
+            ident = format_ident!("{}{}", ident, part);
+        }
         ident
     }
 }
@@ -405,33 +538,35 @@ impl<S: AsRef<str>> From<Vec<S>> for Path {
                 .into_iter()
                 .map(|s| format_ident!("{}", s.as_ref()))
                 .collect(),
+            vec![],
         )
     }
 }
 
 impl ToString for Path {
     fn to_string(&self) -> String {
-        self.0
+        let mut path = self
+            .0
             .iter()
             .map(|i| i.to_string())
             .collect::<Vec<_>>()
-            .join(".")
+            .join(".");
+        for segment in self.1.iter() {
+            path.push_str(&segment.to_string());
+        }
+        path
     }
 }
 
 impl PartialEq for Path {
     fn eq(&self, other: &Self) -> bool {
-        let a = self.0.iter().map(|i| i.to_string()).collect::<Vec<_>>();
-        let b = other.0.iter().map(|i| i.to_string()).collect::<Vec<_>>();
-        a == b
+        self.to_string() == other.to_string()
     }
 }
 
 impl std::hash::Hash for Path {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        for part in self.0.iter() {
-            state.write(part.to_string().as_bytes())
-        }
+        state.write(self.to_string().as_bytes())
     }
 }
 
@@ -444,18 +579,25 @@ impl Parse for Path {
                 if parts.is_empty() {
                     throw!(input, "Expected Path");
                 } else {
-                    return Ok(Path(parts));
+                    return Ok(Path(parts, vec![]));
                 }
             }
             if input.peek(Token![.]) {
                 input.parse::<Token![.]>()?;
             }
             parts.push(input.parse()?);
+            if input.peek(token::Bracket) {
+                if parts.len() < 2 {
+                    throw!(input, "Expected prop before index: {}.prop[index]", parts[0]);
+                }
+                let tail = input.parse_tail()?;
+                return Ok(Path(parts, tail));
+            }
             if !input.peek(Token![.]) {
                 break;
             }
         }
-        Ok(Path(parts))
+        Ok(Path(parts, vec![]))
     }
 }
 
@@ -573,6 +715,10 @@ pub enum Statement {
     Log(LogStatement),
     IfElse(Expr, Vec<Statement>, Option<Box<Statement>>),
     Emit(Path, Expr),
+    /// `e.stop_propagation()`, `e.prevent_default()` or `e.defer()`
+    EventMethod(Path, Ident),
+    /// `e.complete_exit()` for the exit hand argument or `entity.complete_exit()`
+    CompleteExit(Path),
 }
 
 impl Parse for Statement {
@@ -622,8 +768,33 @@ impl Parse for Statement {
                             params,
                         }),
                     )
+                } else if ["stop_propagation", "prevent_default", "defer"]
+                    .contains(&method.to_string().as_str())
+                {
+                    let args;
+                    parenthesized!(args in input);
+                    if !args.is_empty() {
+                        throw!(args, "Unexpected arguments");
+                    }
+                    if input.peek(Token![;]) {
+                        input.parse::<Token![;]>()?;
+                    }
+                    Statement::EventMethod(path, method)
+                } else if &method.to_string() == "complete_exit" {
+                    let args;
+                    parenthesized!(args in input);
+                    if !args.is_empty() {
+                        throw!(args, "Unexpected arguments");
+                    }
+                    if input.peek(Token![;]) {
+                        input.parse::<Token![;]>()?;
+                    }
+                    Statement::CompleteExit(path)
                 } else {
-                    throw!(method, "Only .emit(...) method supported");
+                    throw!(
+                        method,
+                        "Only .emit(...), .stop_propagation(), .prevent_default(), .defer() and .complete_exit() methods supported"
+                    );
                 }
             // assign
             // entity.prop = value
@@ -652,16 +823,24 @@ impl Statement {
     pub fn build(&self, mut ctx: Ref<HandBuilder>) -> syn::Result<TokenStream> {
         match self {
             Statement::Assign(path, expr) => {
-                let expr = expr.build(ctx)?;
-                ctx.write(path, expr)
+                let from = ctx.guards.len();
+                let expr = expr.build(ctx.clone())?;
+                let stmt = ctx.write(path, expr)?;
+                Ok(ctx.guarded(from, stmt))
+            }
+            Statement::Log(log) => {
+                let from = ctx.guards.len();
+                let stmt = log.build(ctx.clone())?;
+                Ok(ctx.guarded(from, stmt))
             }
-            Statement::Log(log) => log.build(ctx),
             Statement::IfElse(condition, stmts, then) => {
+                let from = ctx.guards.len();
                 let expr = condition.build(ctx.clone())?;
+                let guards = ctx.guards.drain(from..).collect::<Vec<_>>();
                 let mut body = quote! {};
                 for stmt in stmts.iter() {
                     let stmt = stmt.build(ctx.clone())?;
-                    body = quote! { #stmt };
+                    body = quote! { #body #stmt };
                 }
                 let then = if let Some(then) = then {
                     let stmt = then.build(ctx.clone())?;
@@ -669,11 +848,14 @@ impl Statement {
                 } else {
                     quote! {}
                 };
-                Ok(quote! {
+                let stmt = quote! {
                     if { #expr } {
                         #body
                     } #then
-                })
+                };
+                let from = ctx.guards.len();
+                ctx.guards.extend(guards);
+                Ok(ctx.guarded(from, stmt))
             }
             Statement::Emit(path, expr) => {
                 let Expr::Construct(args) = expr else {
@@ -681,14 +863,45 @@ impl Statement {
                 };
                 let mark = &path[0];
                 let signal = &path[1];
-                let args = args.build(ctx)?;
-                Ok(quote! {{
+                let from = ctx.guards.len();
+                let args = args.build(ctx.clone())?;
+                let stmt = quote! {{
                     let _descriptor = #mark.descriptor().#signal();
                     let _args = _descriptor.args().construct(|fields, params| { #args });
                     _commands.add(move |world: &mut ::bevy::prelude::World| {
                         _descriptor.emit(world, #mark.entity, _args);
                     });
-                }})
+                }};
+                Ok(ctx.guarded(from, stmt))
+            }
+            Statement::EventMethod(path, method) => {
+                let event = path.mark();
+                if path.len() > 1 || !ctx.args.contains(&event) {
+                    throw!(event, "Only hand argument can {}", method.to_string());
+                }
+                if method == "stop_propagation" || method == "prevent_default" {
+                    ctx.dispatch = true;
+                    Ok(quote! { _dispatch.#method(); })
+                } else {
+                    Ok(quote! { #event.#method(); })
+                }
+            }
+            Statement::CompleteExit(path) => {
+                let mark = path.mark();
+                if path.len() > 1 {
+                    throw!(mark, "Only hand argument or entity can complete_exit");
+                }
+                let flow = ctx.path("flow");
+                let entity = if ctx.args.contains(&mark) {
+                    quote! { #mark.root }
+                } else if ctx.ctx.variables.get(&mark).map(|m| m.is_entity()).unwrap_or(false) {
+                    quote! { #mark.entity }
+                } else {
+                    throw!(mark, "Only hand argument or entity can complete_exit");
+                };
+                Ok(quote! {
+                    _commands.add(#flow::CompleteExit(#entity));
+                })
             }
         }
     }
@@ -1163,6 +1376,7 @@ mod test {
                 .split(".")
                 .map(|s| format_ident!("{s}"))
                 .collect(),
+            vec![],
         ))
     }
     fn group<G: Into<Box<Expr>>>(value: G) -> Expr {
@@ -1182,6 +1396,25 @@ mod test {
         assert_eq!(expr("1 / 2"), div(1, 2));
     }
     #[test]
+    fn test_indexed_path() {
+        let Expr::Read(path) = expr("list.items[3].name") else {
+            panic!("Expected read");
+        };
+        assert_eq!(path.to_string(), "list.items[3].name");
+        assert_eq!(path.var().to_string(), "_v_list_items_3__name");
+        let Expr::Read(path) = expr("inv.slots[\"gold\"]") else {
+            panic!("Expected read");
+        };
+        assert_eq!(path.to_string(), "inv.slots[\"gold\"]");
+        assert_eq!(
+            expr("list.items[0].name.fmt(\"{}\")"),
+            Expr::Format(Expr::Read(expr_path("list.items[0].name")).into(), "{}".into()),
+        );
+    }
+    fn expr_path(from: &'static str) -> Path {
+        parse2::<Path>(from.parse().unwrap()).unwrap()
+    }
+    #[test]
     fn test_expr_basic_op_priority() {
         let e = expr("1 + 2 * 3");
         assert_eq!(e, add(1, mul(2, 3)));
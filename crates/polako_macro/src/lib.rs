@@ -59,7 +59,7 @@ pub fn element_derive(input: TokenStream) -> TokenStream {
     })
 }
 
-#[proc_macro_derive(Signal, attributes(construct, param, prop))]
+#[proc_macro_derive(Signal, attributes(construct, param, prop, signal))]
 pub fn signal_derive(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     TokenStream::from(match DeriveSignal::build_from_derive(input) {
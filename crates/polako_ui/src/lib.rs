@@ -1,5 +1,11 @@
 use bevy::{prelude::{DerefMut, Deref}, math::Vec2, ecs::{component::Component, query::{WorldQuery, Without, With, Changed, Or}, system::{Query, Resource, Commands, ResMut, Local, Res}, entity::{Entity, Entities}, removal_detection::RemovedComponents}, hierarchy::{Children, Parent}, utils::{HashMap, HashSet}, window::{Window, PrimaryWindow}, log::warn};
 use polako_channel::Channel;
+
+mod pointer;
+pub use pointer::*;
+
+#[cfg(test)]
+mod tests;
 // use taffy::
 
 #[derive(Component)]
@@ -253,6 +259,8 @@ pub struct Node {
 pub struct UiSurface {
     tree: taffy::Taffy,
     entity_to_taffy: HashMap<Entity, taffy::node::Node>,
+    /// The creation order of the nodes, the map doesn't keep it.
+    node_order: HashMap<Entity, usize>,
 }
 
 impl UiSurface {
@@ -265,10 +273,16 @@ impl UiSurface {
             self.tree.set_style(*node, style).unwrap();
         } else {
             let node = self.tree.new_leaf(style).unwrap();
-            self.entity_to_taffy.insert(entity, node);
+            self.insert_node(entity, node);
         }
     }
 
+    fn insert_node(&mut self, entity: Entity, node: taffy::node::Node) {
+        self.entity_to_taffy.insert(entity, node);
+        let order = self.node_order.len();
+        self.node_order.entry(entity).or_insert(order);
+    }
+
     pub fn edit_style<F: FnOnce(&mut taffy::style::Style)>(&mut self, entity: Entity, edit: F) {
         if let Some(node) = self.entity_to_taffy.get(&entity) {
             let mut style = self.tree.style(*node).unwrap().clone();
@@ -278,7 +292,7 @@ impl UiSurface {
             let mut style = taffy::style::Style::default();
             edit(&mut style);
             let node = self.tree.new_leaf(style).unwrap();
-            self.entity_to_taffy.insert(entity, node);
+            self.insert_node(entity, node);
         }
     }
 
@@ -293,6 +307,7 @@ impl Default for UiSurface {
         UiSurface {
             tree: taffy::Taffy::new(),
             entity_to_taffy: HashMap::new(),
+            node_order: HashMap::new(),
         }
     }
 }
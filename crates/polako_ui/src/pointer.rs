@@ -0,0 +1,68 @@
+use bevy::{
+    ecs::system::{Query, Res, SystemParamItem},
+    math::Vec2,
+    utils::HashMap,
+};
+use polako_input::{PointerBackend, PointerHit, PointerLocation, PointerShape, SURFACE_DEPTH};
+
+use crate::UiSurface;
+
+/// Hits the nodes of the `UiSurface` layout, the children are above the parents,
+/// the later siblings are above the former ones and the roots with the later
+/// created nodes are above the former ones, no matter the entity ids. The hits are in the [`SURFACE_DEPTH`] band:
+/// `app.add_plugins(PointerBackendPlugin::<SurfaceBackend>::default())`.
+pub struct SurfaceBackend;
+
+impl PointerBackend for SurfaceBackend {
+    type Param = (
+        Res<'static, UiSurface>,
+        Query<'static, 'static, &'static PointerShape>,
+    );
+    fn hit_test(
+        location: &PointerLocation,
+        param: &SystemParamItem<Self::Param>,
+    ) -> Vec<PointerHit> {
+        let (surface, shapes) = param;
+        let entities = surface
+            .entity_to_taffy
+            .iter()
+            .map(|(entity, node)| (*node, *entity))
+            .collect::<HashMap<_, _>>();
+        let mut roots = surface
+            .entity_to_taffy
+            .iter()
+            .filter(|(_, node)| surface.tree.parent(**node).is_none())
+            .map(|(entity, node)| (*entity, *node))
+            .collect::<Vec<_>>();
+        // the surface keeps the nodes in the hash map, the roots are painted
+        // in the creation order to keep the depths stable between the frames
+        roots.sort_by_key(|(entity, _)| surface.node_order.get(entity).copied());
+        let mut hits = vec![];
+        let mut order = 0;
+        for (_, root) in roots {
+            let mut stack = vec![(root, Vec2::ZERO)];
+            // depth-first, the paint order
+            while let Some((node, offset)) = stack.pop() {
+                let Ok(layout) = surface.tree.layout(node) else {
+                    continue;
+                };
+                order += 1;
+                let min = offset + Vec2::new(layout.location.x, layout.location.y);
+                let size = Vec2::new(layout.size.width, layout.size.height);
+                let rel = location.viewport - min;
+                let inside = rel.cmpge(Vec2::ZERO).all() && rel.cmplt(size).all();
+                if let Some(entity) = entities.get(&node).copied().filter(|_| inside) {
+                    if shapes.get(entity).map_or(true, |s| s.contains(rel, size)) {
+                        hits.push(PointerHit {
+                            entity,
+                            depth: SURFACE_DEPTH + order as f32,
+                        });
+                    }
+                }
+                let children = surface.tree.children(node).unwrap_or_default();
+                stack.extend(children.into_iter().rev().map(|child| (child, min)));
+            }
+        }
+        hits
+    }
+}
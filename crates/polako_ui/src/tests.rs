@@ -0,0 +1,59 @@
+use bevy::{ecs::system::SystemState, prelude::World};
+use polako_input::{PointerBackend, PointerLocation, SURFACE_DEPTH};
+
+use super::*;
+
+fn style(width: f32, height: f32) -> taffy::style::Style {
+    taffy::style::Style {
+        size: taffy::geometry::Size::from_points(width, height),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_surface_backend_hits() {
+    let mut world = World::new();
+    // the ids don't follow the creation order of the nodes
+    let second = world.spawn_empty().id();
+    let child = world.spawn_empty().id();
+    let first = world.spawn_empty().id();
+    let mut surface = UiSurface::default();
+    let child_node = surface.tree.new_leaf(style(10., 10.)).unwrap();
+    let first_node = surface
+        .tree
+        .new_with_children(style(100., 100.), &[child_node])
+        .unwrap();
+    let second_node = surface.tree.new_leaf(style(50., 50.)).unwrap();
+    for root in [first_node, second_node] {
+        surface
+            .tree
+            .compute_layout(root, taffy::geometry::Size::MAX_CONTENT)
+            .unwrap();
+    }
+    surface.insert_node(first, first_node);
+    surface.insert_node(child, child_node);
+    surface.insert_node(second, second_node);
+    world.insert_resource(surface);
+
+    let mut state = SystemState::<<SurfaceBackend as PointerBackend>::Param>::new(&mut world);
+    let param = state.get(&world);
+    let hit_test = |x: f32, y: f32| {
+        let location = PointerLocation {
+            window: None,
+            camera: None,
+            abs: Vec2::new(x, y),
+            viewport: Vec2::new(x, y),
+        };
+        let mut hits = SurfaceBackend::hit_test(&location, &param);
+        assert!(hits.iter().all(|hit| hit.depth >= SURFACE_DEPTH));
+        hits.sort_by(|a, b| b.depth.total_cmp(&a.depth));
+        hits.into_iter().map(|hit| hit.entity).collect::<Vec<_>>()
+    };
+    // the later created root is above the former one, the child is above the parent
+    assert_eq!(vec![second, child, first], hit_test(5., 5.));
+    assert_eq!(vec![second, first], hit_test(20., 20.));
+    assert_eq!(vec![first], hit_test(70., 70.));
+    assert!(hit_test(120., 5.).is_empty());
+    // the hits are the same every frame
+    assert_eq!(vec![second, child, first], hit_test(5., 5.));
+}
@@ -57,7 +57,7 @@ fn main() {
             }),
             ..default()
         }))
-        .add_plugins(FlowPlugin)
+        .add_plugins(FlowPlugin::default())
         .add_plugins(PolakoInputPlugin)
         .add_systems(Startup, example)
         .add_systems(Update, ui_text_system)
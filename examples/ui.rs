@@ -18,7 +18,7 @@ fn main() {
             }),
             ..default()
         }))
-        .add_plugins(FlowPlugin)
+        .add_plugins(FlowPlugin::default())
         .add_systems(Startup, hello_world)
         .add_systems(Update, ui_text_system)
         .add_systems(Update, div_system)
@@ -19,3 +19,8 @@ pub mod eml {
 pub mod flow {
     pub use polako_flow::*;
 }
+
+pub mod testing;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,235 @@
+//! Headless harness for testing `eml!` trees, binds and hands without a window:
+//! ```
+//! use bevy::prelude::*;
+//! use polako::eml::*;
+//! use polako::flow::input::ClickSignal;
+//! use polako::testing::PolakoTestApp;
+//! use polako_constructivism::Is;
+//! use polako_input::PointerInputData;
+//!
+//! #[derive(Element)]
+//! #[construct(Div -> Empty)]
+//! #[signals(click: ClickSignal)]
+//! pub struct Div {}
+//!
+//! impl ElementBuilder for Div {
+//!     fn build_element(content: Vec<Entity>) -> Blueprint<Self> {
+//!         blueprint! { Div::Base [[ content ]] }
+//!     }
+//! }
+//!
+//! impl DivDesign {
+//!     pub fn push_content<E: Element + Is<Div>>(
+//!         &self,
+//!         _: &mut World,
+//!         content: &mut Vec<Entity>,
+//!         model: EntityMark<E>,
+//!     ) -> Implemented {
+//!         content.push(model.entity);
+//!         Implemented
+//!     }
+//! }
+//!
+//! #[derive(Behavior, Default)]
+//! pub struct UiText {
+//!     pub text: String,
+//! }
+//!
+//! #[derive(Element)]
+//! #[construct(Label -> UiText -> Div)]
+//! pub struct Label;
+//!
+//! impl ElementBuilder for Label {
+//!     fn build_element(_: Vec<Entity>) -> Blueprint<Self> {
+//!         blueprint! { Label::Base }
+//!     }
+//! }
+//!
+//! fn main() {
+//!     let mut app = PolakoTestApp::new();
+//!     app.spawn(eml! {
+//!         Div [
+//!             label: Label { .text: "0" },
+//!             button: Div { .on.click: () => { label.text = "1"; } },
+//!         ]
+//!     });
+//!     app.step(1);
+//!     let button = app.find("button");
+//!     app.emit_pointer(button, PointerInputData::Click);
+//!     app.step(1);
+//!     app.assert_prop::<UiText, _>("label", |p, c| p.text(c), "1".to_string());
+//! }
+//! ```
+use std::{fmt::Debug, time::Duration};
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use polako_constructivism::{Construct, Get, Singleton, Value};
+use polako_eml::{ElementBuilder, Eml, EmlMarks};
+use polako_flow::FlowPlugin;
+use polako_input::{
+    GestureInput, KeyInput, KeyInputData, PointerCapture, PointerId, PointerInput,
+    PointerInputData, PointerInputPosition, ScrollInput,
+};
+
+/// The `App` with `FlowPlugin` and manually advanced `Time`.
+pub struct PolakoTestApp {
+    pub app: App,
+    frame_time: Duration,
+}
+
+impl Default for PolakoTestApp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PolakoTestApp {
+    /// Creates the app with the 60 fps frame time.
+    pub fn new() -> Self {
+        let frame_time = Duration::from_secs_f64(1. / 60.);
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(HierarchyPlugin);
+        app.add_plugins(FlowPlugin::default());
+        app.add_event::<PointerInput>();
+        app.add_event::<KeyInput>();
+        app.add_event::<GestureInput>();
+        app.add_event::<ScrollInput>();
+        app.init_resource::<PointerCapture>();
+        app.init_resource::<EmlMarks>();
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(frame_time));
+        PolakoTestApp { app, frame_time }
+    }
+
+    pub fn world(&self) -> &World {
+        &self.app.world
+    }
+
+    pub fn world_mut(&mut self) -> &mut World {
+        &mut self.app.world
+    }
+
+    /// Sets the time advanced by each `step`.
+    pub fn set_frame_time(&mut self, frame_time: Duration) {
+        self.frame_time = frame_time;
+        self.app
+            .insert_resource(TimeUpdateStrategy::ManualDuration(frame_time));
+    }
+
+    pub fn frame_time(&self) -> Duration {
+        self.frame_time
+    }
+
+    /// Runs `frames` updates, `Time` is advanced by the frame time every update.
+    pub fn step(&mut self, frames: usize) {
+        for _ in 0..frames {
+            self.app.update();
+        }
+    }
+
+    /// Runs the single update with the `Time` advanced by `delta`.
+    pub fn advance(&mut self, delta: Duration) {
+        self.app
+            .insert_resource(TimeUpdateStrategy::ManualDuration(delta));
+        self.app.update();
+        self.app
+            .insert_resource(TimeUpdateStrategy::ManualDuration(self.frame_time));
+    }
+
+    /// Writes the eml into the new entity and returns it. Binds and hands
+    /// are registered with the next `step`.
+    pub fn spawn<R: ElementBuilder>(&mut self, eml: Eml<R>) -> Entity {
+        let entity = self.app.world.spawn_empty().id();
+        eml.write(&mut self.app.world, entity);
+        entity
+    }
+
+    /// Sends the pointer input for the entity, hands are executed with the next `step`.
+    pub fn emit_pointer(&mut self, entity: Entity, data: PointerInputData) {
+        self.emit_pointer_at(entity, data, PointerInputPosition::default());
+    }
+
+    pub fn emit_pointer_at(
+        &mut self,
+        entity: Entity,
+        data: PointerInputData,
+        position: PointerInputPosition,
+    ) {
+        self.app
+            .world
+            .get_resource_or_insert_with(Events::<PointerInput>::default)
+            .send(PointerInput {
+                entity,
+                pointer: PointerId::Mouse,
+                button: data.is_button().then_some(MouseButton::Left),
+                position,
+                data,
+            });
+    }
+
+    /// Sends the keyboard input for the entity as if it was focused.
+    pub fn emit_key(&mut self, entity: Entity, data: KeyInputData) {
+        self.app
+            .world
+            .get_resource_or_insert_with(Events::<KeyInput>::default)
+            .send(KeyInput { entity, data });
+    }
+
+    /// Returns the entity declared with the `mark` in `eml!`.
+    pub fn try_find(&mut self, mark: &str) -> Option<Entity> {
+        let world = &self.app.world;
+        world
+            .resource::<EmlMarks>()
+            .get(mark)
+            .find(|e| world.get_entity(*e).is_some())
+    }
+
+    /// Returns the entity declared with the `mark` in `eml!`, panics if there is no such mark.
+    pub fn find(&mut self, mark: &str) -> Entity {
+        self.try_find(mark)
+            .unwrap_or_else(|| panic!("No element with mark `{mark}`"))
+    }
+
+    /// Reads the prop of the marked element using `Props<Get>`:
+    /// ```ignore
+    /// let text = app.prop::<UiText, _>("label", |p, c| p.text(c));
+    /// ```
+    pub fn prop<C, V>(
+        &mut self,
+        mark: &str,
+        getter: impl for<'a> FnOnce(&'static C::Props<Get>, &'a C) -> Value<'a, V>,
+    ) -> V
+    where
+        C: Component + Construct,
+        V: Clone,
+    {
+        let entity = self.find(mark);
+        let Some(host) = self.app.world.get::<C>(entity) else {
+            panic!(
+                "Element `{mark}` has no {} component",
+                std::any::type_name::<C>()
+            );
+        };
+        let props = <C::Props<Get> as Singleton>::instance();
+        getter(props, host).get()
+    }
+
+    /// Asserts the prop of the marked element equals to `expected`.
+    pub fn assert_prop<C, V>(
+        &mut self,
+        mark: &str,
+        getter: impl for<'a> FnOnce(&'static C::Props<Get>, &'a C) -> Value<'a, V>,
+        expected: V,
+    ) where
+        C: Component + Construct,
+        V: Clone + PartialEq + Debug,
+    {
+        let value = self.prop(mark, getter);
+        assert_eq!(
+            value,
+            expected,
+            "Unexpected value of `{mark}` {}",
+            std::any::type_name::<C>()
+        );
+    }
+}
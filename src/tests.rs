@@ -0,0 +1,79 @@
+use std::time::Duration;
+
+use bevy::{ecs::system::StaticSystemParam, prelude::*};
+use polako_constructivism::Value;
+use polako_flow::{input::ClickSignal, EntityFlow, EntityProp, Reader, WorldFlow, Writer};
+use polako_input::{PointerInput, PointerInputData};
+
+use crate::testing::PolakoTestApp;
+
+#[derive(Component)]
+struct Counter(usize);
+
+fn counter_value(host: &Counter) -> Value<usize> {
+    Value::Val(host.0)
+}
+
+fn set_counter_value(host: &mut Counter, value: usize) {
+    host.0 = value;
+}
+
+#[derive(Resource, Default)]
+struct Clicks(usize);
+
+fn count_click(_: &PointerInput, clicks: &mut StaticSystemParam<ResMut<'static, Clicks>>) {
+    clicks.0 += 1;
+}
+
+#[test]
+fn test_step_applies_binds() {
+    let mut app = PolakoTestApp::new();
+    let world = app.world_mut();
+    let source = world.spawn(Counter(1)).id();
+    let target = world.spawn(Counter(0)).id();
+    world.bind_component_to_component(
+        source.get(Reader::Func(counter_value)),
+        target.set(Writer::Func {
+            get: counter_value,
+            set: set_counter_value,
+        }),
+    );
+    app.step(1);
+    assert_eq!(1, app.world().get::<Counter>(target).unwrap().0);
+
+    app.world_mut().get_mut::<Counter>(source).unwrap().0 = 5;
+    app.step(1);
+    assert_eq!(5, app.world().get::<Counter>(target).unwrap().0);
+}
+
+#[test]
+fn test_emit_pointer_executes_hands() {
+    let mut app = PolakoTestApp::new();
+    app.world_mut().init_resource::<Clicks>();
+    let button = app.world_mut().spawn_empty().id();
+    app.world_mut()
+        .entity_mut(button)
+        .register_signal_handler::<ClickSignal, ResMut<'static, Clicks>, _>(count_click);
+    app.emit_pointer(button, PointerInputData::Click);
+    assert_eq!(0, app.world().resource::<Clicks>().0);
+
+    app.step(1);
+    assert_eq!(1, app.world().resource::<Clicks>().0);
+
+    // the input is handled once
+    app.step(2);
+    assert_eq!(1, app.world().resource::<Clicks>().0);
+}
+
+#[test]
+fn test_advance_time() {
+    let mut app = PolakoTestApp::new();
+    app.step(1);
+    app.advance(Duration::from_millis(100));
+    assert_eq!(
+        Duration::from_millis(100),
+        app.world().resource::<Time>().delta()
+    );
+    app.step(1);
+    assert_eq!(app.frame_time(), app.world().resource::<Time>().delta());
+}